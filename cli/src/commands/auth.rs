@@ -2,8 +2,8 @@ use anyhow::Result;
 
 use crate::github::auth::check_auth;
 
-pub async fn run() -> Result<()> {
-    let status = check_auth().await?;
+pub async fn run(hostname: &str) -> Result<()> {
+    let status = check_auth(hostname).await?;
     println!("{}", status);
     Ok(())
 }
@@ -0,0 +1,17 @@
+use anyhow::Result;
+
+use crate::github::cache;
+
+pub fn run_clear() -> Result<()> {
+    let removed = cache::clear()?;
+
+    println!(
+        "{}",
+        serde_json::json!({
+            "success": true,
+            "removed": removed,
+        })
+    );
+
+    Ok(())
+}
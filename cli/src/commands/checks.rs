@@ -0,0 +1,21 @@
+use anyhow::Result;
+
+use crate::github::client::GitHubClient;
+use crate::github::types::{ChecksResponse, ChecksSummary};
+
+pub async fn run(url: &str) -> Result<()> {
+    let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
+
+    let pr = client.get_pr(&pr_ref).await?;
+    let checks = client.get_checks(&pr_ref, &pr.head_sha).await?;
+
+    let response = ChecksResponse {
+        head_sha: pr.head_sha,
+        summary: ChecksSummary::from_checks(checks),
+    };
+
+    println!("{}", serde_json::to_string(&response)?);
+
+    Ok(())
+}
@@ -1,110 +1,173 @@
 use anyhow::Result;
 
+use crate::commands::diff::map_line_between_revisions;
 use crate::github::client::GitHubClient;
-use crate::github::types::CommentResponse;
+use crate::github::types::{CommentResponse, PrRef};
 
-pub async fn run(
-    url: &str,
-    path: &str,
-    line: u32,
-    side: &str,
-    body: &str,
-    start_line: Option<u32>,
-    start_side: Option<&str>,
-) -> Result<()> {
-    let client = GitHubClient::new()?;
+/// A comment on a line or line range of a file
+#[derive(Debug, Clone)]
+pub struct LineComment {
+    pub path: String,
+    pub line: u32,
+    pub side: String,
+    pub body: String,
+    pub start_line: Option<u32>,
+    pub start_side: Option<String>,
+    /// Commit the lines refer to (as shown by `fetch --commit`)
+    pub commit: Option<String>,
+}
+
+pub async fn run(url: &str, comment: LineComment) -> Result<()> {
     let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
 
     let pr = client.get_pr(&pr_ref).await?;
+    let response = add_line_comment(&client, &pr_ref, &pr.head_sha, &comment).await?;
+
+    println!("{}", serde_json::to_string(&response)?);
 
-    match client
+    Ok(())
+}
+
+/// Add a line comment. With `commit`, the lines refer to that commit; the
+/// comment is moved to the PR head when the lines are unchanged there, and
+/// otherwise left on the commit itself.
+pub(crate) async fn add_line_comment(
+    client: &GitHubClient,
+    pr_ref: &PrRef,
+    head_sha: &str,
+    comment: &LineComment,
+) -> Result<CommentResponse> {
+    let (commit_id, line, start_line) = match comment.commit.as_deref() {
+        Some(commit) => anchor_to_head(
+            commit,
+            head_sha,
+            &comment.path,
+            &comment.side,
+            comment.line,
+            comment.start_line,
+        )?,
+        None => (head_sha.to_string(), comment.line, comment.start_line),
+    };
+
+    let result = client
         .add_review_comment(
-            &pr_ref,
-            &pr.head_sha,
-            path,
+            pr_ref,
+            &commit_id,
+            &comment.path,
             line,
-            side,
-            body,
+            &comment.side,
+            &comment.body,
             start_line,
-            start_side,
+            comment.start_side.as_deref(),
         )
-        .await
-    {
-        Ok(comment) => {
-            let response = CommentResponse {
-                success: true,
-                comment_id: Some(comment.id),
-                html_url: Some(comment.html_url),
-                error: None,
-            };
-            println!("{}", serde_json::to_string(&response)?);
-        }
-        Err(e) => {
-            let response = CommentResponse {
-                success: false,
-                comment_id: None,
-                html_url: None,
-                error: Some(e.to_string()),
-            };
-            println!("{}", serde_json::to_string(&response)?);
-        }
+        .await;
+
+    Ok(comment_response(result.map(|c| (c.id, Some(c.html_url)))))
+}
+
+/// Turn the outcome of a comment mutation into the printed response
+pub(crate) fn comment_response(result: Result<(u64, Option<String>)>) -> CommentResponse {
+    match result {
+        Ok((comment_id, html_url)) => CommentResponse {
+            success: true,
+            comment_id: Some(comment_id),
+            html_url,
+            error: None,
+        },
+        Err(e) => CommentResponse {
+            success: false,
+            comment_id: None,
+            html_url: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+/// Translate a comment range on `commit` to the PR head. LEFT-side lines
+/// belong to the commit's parent, so they always stay on the commit.
+fn anchor_to_head(
+    commit: &str,
+    head_sha: &str,
+    path: &str,
+    side: &str,
+    line: u32,
+    start_line: Option<u32>,
+) -> Result<(String, u32, Option<u32>)> {
+    let on_commit = (commit.to_string(), line, start_line);
+    if side.eq_ignore_ascii_case("LEFT") {
+        return Ok(on_commit);
     }
 
+    let Some(head_line) = map_line_between_revisions(commit, head_sha, path, line)? else {
+        return Ok(on_commit);
+    };
+    let head_start_line = match start_line {
+        Some(start) => match map_line_between_revisions(commit, head_sha, path, start)? {
+            Some(mapped) => Some(mapped),
+            None => return Ok(on_commit),
+        },
+        None => None,
+    };
+
+    Ok((head_sha.to_string(), head_line, head_start_line))
+}
+
+/// Post a top-level comment on the PR conversation rather than a file line
+pub async fn run_pr_level(url: &str, body: &str) -> Result<()> {
+    let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
+
+    let response = add_pr_level_comment(&client, &pr_ref, body).await;
+    println!("{}", serde_json::to_string(&response)?);
+
     Ok(())
 }
 
+pub(crate) async fn add_pr_level_comment(
+    client: &GitHubClient,
+    pr_ref: &PrRef,
+    body: &str,
+) -> CommentResponse {
+    let result = client.add_issue_comment(pr_ref, body).await;
+    comment_response(result.map(|c| (c.id, Some(c.html_url))))
+}
+
 pub async fn run_edit(url: &str, comment_id: u64, body: &str) -> Result<()> {
-    let client = GitHubClient::new()?;
     let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
 
-    match client.edit_review_comment(&pr_ref, comment_id, body).await {
-        Ok(comment) => {
-            let response = CommentResponse {
-                success: true,
-                comment_id: Some(comment.id),
-                html_url: Some(comment.html_url),
-                error: None,
-            };
-            println!("{}", serde_json::to_string(&response)?);
-        }
-        Err(e) => {
-            let response = CommentResponse {
-                success: false,
-                comment_id: None,
-                html_url: None,
-                error: Some(e.to_string()),
-            };
-            println!("{}", serde_json::to_string(&response)?);
-        }
-    }
+    let response = edit_comment(&client, &pr_ref, comment_id, body).await;
+    println!("{}", serde_json::to_string(&response)?);
 
     Ok(())
 }
 
+pub(crate) async fn edit_comment(
+    client: &GitHubClient,
+    pr_ref: &PrRef,
+    comment_id: u64,
+    body: &str,
+) -> CommentResponse {
+    let result = client.edit_review_comment(pr_ref, comment_id, body).await;
+    comment_response(result.map(|c| (c.id, Some(c.html_url))))
+}
+
 pub async fn run_delete(url: &str, comment_id: u64) -> Result<()> {
-    let client = GitHubClient::new()?;
     let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
 
-    match client.delete_review_comment(&pr_ref, comment_id).await {
-        Ok(()) => {
-            let response = CommentResponse {
-                success: true,
-                comment_id: Some(comment_id),
-                html_url: None,
-                error: None,
-            };
-            println!("{}", serde_json::to_string(&response)?);
-        }
-        Err(e) => {
-            let response = CommentResponse {
-                success: false,
-                comment_id: None,
-                html_url: None,
-                error: Some(e.to_string()),
-            };
-            println!("{}", serde_json::to_string(&response)?);
-        }
-    }
+    let response = delete_comment(&client, &pr_ref, comment_id).await;
+    println!("{}", serde_json::to_string(&response)?);
 
     Ok(())
 }
+
+pub(crate) async fn delete_comment(
+    client: &GitHubClient,
+    pr_ref: &PrRef,
+    comment_id: u64,
+) -> CommentResponse {
+    let result = client.delete_review_comment(pr_ref, comment_id).await;
+    comment_response(result.map(|()| (comment_id, None)))
+}
@@ -1,17 +1,345 @@
 use anyhow::Result;
+use std::collections::HashMap;
 
+use crate::commands::diff::{get_pr_review_files, relocate_line_between_revisions};
+use crate::commands::fetch::{ensure_commit_fetched, ensure_pr_commits_available};
 use crate::github::client::GitHubClient;
-use crate::github::types::CommentsResponse;
+use crate::github::types::{
+    CommentThread, CommentsResponse, LineKind, PrRef, RelocationStatus, ReviewComment, ReviewFile,
+    ThreadedCommentsResponse,
+};
 
-pub async fn run(url: &str) -> Result<()> {
-    let client = GitHubClient::new()?;
+pub async fn run(url: &str, threaded: bool) -> Result<()> {
     let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
 
-    let comments = client.get_review_comments(&pr_ref).await?;
+    let (pr, mut comments, viewer) = tokio::try_join!(
+        client.get_pr(&pr_ref),
+        client.get_review_comments(&pr_ref),
+        async {
+            if threaded {
+                client.get_viewer().await
+            } else {
+                Ok(String::new())
+            }
+        }
+    )?;
 
-    let response = CommentsResponse { comments };
+    // Relocation and block mapping diff commits locally, so skip them
+    // outside a clone of the repo.
+    if ensure_pr_commits_available(&pr_ref, &pr).is_ok() {
+        relocate_outdated_comments(&pr_ref, comments.iter_mut(), &pr.head_sha);
+        if let Ok(files) = get_pr_review_files(&pr.base_sha, &pr.head_sha, true) {
+            locate_comments(&files, comments.iter_mut());
+        }
+    }
 
-    println!("{}", serde_json::to_string(&response)?);
+    if threaded {
+        let response = ThreadedCommentsResponse {
+            threads: group_threads(comments, &viewer),
+            viewer,
+        };
+        println!("{}", serde_json::to_string(&response)?);
+    } else {
+        let response = CommentsResponse { comments };
+        println!("{}", serde_json::to_string(&response)?);
+    }
 
     Ok(())
 }
+
+/// Nest replies under their root comment. Replies whose root is missing
+/// start their own thread.
+fn group_threads(comments: Vec<ReviewComment>, viewer: &str) -> Vec<CommentThread> {
+    let parents: HashMap<u64, Option<u64>> =
+        comments.iter().map(|c| (c.id, c.in_reply_to_id)).collect();
+    let root_of = |mut id: u64| {
+        // GitHub points replies at the root, but follow chains just in case.
+        let mut hops = 0;
+        while let Some(Some(parent)) = parents.get(&id) {
+            if !parents.contains_key(parent) || hops > parents.len() {
+                break;
+            }
+            id = *parent;
+            hops += 1;
+        }
+        id
+    };
+
+    let mut roots: Vec<ReviewComment> = Vec::new();
+    let mut replies: HashMap<u64, Vec<ReviewComment>> = HashMap::new();
+    for comment in comments {
+        let root = root_of(comment.id);
+        if root == comment.id {
+            roots.push(comment);
+        } else {
+            replies.entry(root).or_default().push(comment);
+        }
+    }
+
+    roots
+        .into_iter()
+        .map(|root| {
+            let mut replies = replies.remove(&root.id).unwrap_or_default();
+            replies.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+            let mut participants: Vec<String> = Vec::new();
+            for author in std::iter::once(&root).chain(&replies).map(|c| &c.author) {
+                if !participants.contains(author) {
+                    participants.push(author.clone());
+                }
+            }
+            let last = replies.last().unwrap_or(&root);
+
+            CommentThread {
+                last_activity: last.created_at.clone(),
+                viewer_replied_last: last.author == viewer,
+                participants,
+                root,
+                replies,
+            }
+        })
+        .collect()
+}
+
+/// Follow outdated comments (no current `line`) from the commit they were
+/// left on to `head_sha`. Comments whose original commit can't be fetched
+/// are left without a relocation.
+pub(crate) fn relocate_outdated_comments<'a>(
+    pr_ref: &PrRef,
+    comments: impl IntoIterator<Item = &'a mut ReviewComment>,
+    head_sha: &str,
+) {
+    let mut fetched: HashMap<String, bool> = HashMap::new();
+
+    for comment in comments.into_iter().filter(|c| is_outdated(c)) {
+        let (Some(original_commit), Some(original_line)) =
+            (comment.original_commit_id.clone(), comment.original_line)
+        else {
+            continue;
+        };
+
+        let available = *fetched
+            .entry(original_commit.clone())
+            .or_insert_with(|| ensure_commit_fetched(pr_ref, &original_commit).is_ok());
+        if !available {
+            continue;
+        }
+
+        comment.relocation = relocate_line_between_revisions(
+            &original_commit,
+            head_sha,
+            &comment.path,
+            original_line,
+        )
+        .ok();
+    }
+}
+
+/// Work out which change block each comment sits on and on what kind of
+/// line. Outdated comments are placed by their relocated line, if any.
+pub(crate) fn locate_comments<'a>(
+    files: &[ReviewFile],
+    comments: impl IntoIterator<Item = &'a mut ReviewComment>,
+) {
+    for comment in comments {
+        let file = files.iter().find(|f| f.path == comment.path);
+        let (change_block, line_kind) = match (file, current_line(comment)) {
+            (Some(file), Some(line)) => locate_line(file, &comment.side, line),
+            _ => (None, None),
+        };
+        comment.change_block = change_block;
+        comment.line_kind = line_kind;
+        comment.outside_diff = Some(change_block.is_none());
+    }
+}
+
+/// The comment's line at the PR head, falling back to its relocated line
+fn current_line(comment: &ReviewComment) -> Option<u32> {
+    comment.line.or_else(|| {
+        comment
+            .relocation
+            .filter(|r| r.status != RelocationStatus::Gone)
+            .and_then(|r| r.line)
+    })
+}
+
+fn locate_line(file: &ReviewFile, side: &str, line: u32) -> (Option<usize>, Option<LineKind>) {
+    if side.eq_ignore_ascii_case("LEFT") {
+        let block = file.change_blocks.iter().position(|block| {
+            block
+                .deletion_groups
+                .iter()
+                .any(|group| group.old_line_numbers.contains(&line))
+        });
+        return match block {
+            Some(index) => (Some(index), Some(LineKind::Deleted)),
+            None => (None, Some(LineKind::Context)),
+        };
+    }
+
+    let block = file
+        .change_blocks
+        .iter()
+        .position(|block| (block.start_line..=block.end_line).contains(&line));
+    let kind = match block.map(|index| &file.change_blocks[index]) {
+        Some(block) if block.changed_lines.contains(&line) => LineKind::Changed,
+        Some(block) if block.added_lines.contains(&line) => LineKind::Added,
+        _ => LineKind::Context,
+    };
+    (block, Some(kind))
+}
+
+/// Outdated right-side comments; left-side lines refer to the base, which
+/// a push to the PR doesn't move.
+fn is_outdated(comment: &ReviewComment) -> bool {
+    comment.line.is_none() && comment.side.eq_ignore_ascii_case("RIGHT")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::parser::parse_patch;
+    use crate::github::types::{FileStatus, Relocation};
+
+    fn review_file(path: &str, patch: &str) -> ReviewFile {
+        ReviewFile {
+            path: path.to_string(),
+            status: FileStatus::Modified,
+            additions: 0,
+            deletions: 0,
+            content: None,
+            change_blocks: parse_patch(patch),
+            annotations: Vec::new(),
+            viewed_state: None,
+        }
+    }
+
+    fn comment(path: &str, line: Option<u32>, side: &str) -> ReviewComment {
+        ReviewComment {
+            id: 1,
+            path: path.to_string(),
+            line,
+            side: side.to_string(),
+            body: "Looks off".to_string(),
+            author: "reviewer".to_string(),
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            original_line: line,
+            ..Default::default()
+        }
+    }
+
+    const PATCH: &str = "\
+@@ -1,4 +1,5 @@
+ one
+-two
++TWO
+ three
++extra
+ four
+@@ -20,3 +21,2 @@
+ twenty
+-gone
+ twenty-two";
+
+    fn reply(id: u64, to: u64, author: &str, created_at: &str) -> ReviewComment {
+        ReviewComment {
+            id,
+            in_reply_to_id: Some(to),
+            author: author.to_string(),
+            created_at: created_at.to_string(),
+            ..comment("src/lib.rs", Some(2), "RIGHT")
+        }
+    }
+
+    #[test]
+    fn group_threads_nests_replies_in_order() {
+        let comments = vec![
+            comment("src/lib.rs", Some(2), "RIGHT"),
+            reply(3, 1, "me", "2026-01-03T00:00:00Z"),
+            reply(2, 1, "author", "2026-01-02T00:00:00Z"),
+            ReviewComment {
+                id: 4,
+                ..comment("src/main.rs", Some(8), "RIGHT")
+            },
+        ];
+
+        let threads = group_threads(comments, "me");
+
+        assert_eq!(threads.len(), 2);
+        let ids: Vec<u64> = threads[0].replies.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![2, 3]);
+        assert_eq!(threads[0].participants, vec!["reviewer", "author", "me"]);
+        assert_eq!(threads[0].last_activity, "2026-01-03T00:00:00Z");
+        assert!(threads[0].viewer_replied_last);
+        assert!(threads[1].replies.is_empty());
+        assert!(!threads[1].viewer_replied_last);
+    }
+
+    #[test]
+    fn group_threads_keeps_orphaned_replies() {
+        let threads = group_threads(vec![reply(5, 99, "author", "2026-01-02T00:00:00Z")], "me");
+
+        assert_eq!(threads.len(), 1);
+        assert_eq!(threads[0].root.id, 5);
+    }
+
+    #[test]
+    fn locate_comments_classifies_right_side_lines() {
+        let files = vec![review_file("src/lib.rs", PATCH)];
+        let mut comments = [
+            comment("src/lib.rs", Some(2), "RIGHT"),
+            comment("src/lib.rs", Some(4), "RIGHT"),
+            comment("src/lib.rs", Some(10), "RIGHT"),
+        ];
+
+        locate_comments(&files, comments.iter_mut());
+
+        assert_eq!(comments[0].change_block, Some(0));
+        assert_eq!(comments[0].line_kind, Some(LineKind::Changed));
+        assert_eq!(comments[1].line_kind, Some(LineKind::Added));
+        assert_eq!(comments[1].outside_diff, Some(false));
+        assert_eq!(comments[2].change_block, None);
+        assert_eq!(comments[2].line_kind, Some(LineKind::Context));
+        assert_eq!(comments[2].outside_diff, Some(true));
+    }
+
+    #[test]
+    fn locate_comments_places_left_side_deletions() {
+        let files = vec![review_file("src/lib.rs", PATCH)];
+        let mut comments = [
+            comment("src/lib.rs", Some(21), "LEFT"),
+            comment("src/lib.rs", Some(20), "LEFT"),
+        ];
+
+        locate_comments(&files, comments.iter_mut());
+
+        assert_eq!(comments[0].change_block, Some(2));
+        assert_eq!(comments[0].line_kind, Some(LineKind::Deleted));
+        assert_eq!(comments[1].change_block, None);
+        assert_eq!(comments[1].outside_diff, Some(true));
+    }
+
+    #[test]
+    fn locate_comments_uses_relocated_line_for_outdated_comments() {
+        let files = vec![review_file("src/lib.rs", PATCH)];
+        let mut relocated = comment("src/lib.rs", None, "RIGHT");
+        relocated.relocation = Some(Relocation {
+            status: RelocationStatus::Moved,
+            line: Some(4),
+        });
+        let mut gone = comment("src/lib.rs", None, "RIGHT");
+        gone.relocation = Some(Relocation {
+            status: RelocationStatus::Gone,
+            line: None,
+        });
+        let mut comments = [relocated, gone, comment("other.rs", Some(1), "RIGHT")];
+
+        locate_comments(&files, comments.iter_mut());
+
+        assert_eq!(comments[0].line_kind, Some(LineKind::Added));
+        assert_eq!(comments[1].outside_diff, Some(true));
+        assert_eq!(comments[1].line_kind, None);
+        assert_eq!(comments[2].outside_diff, Some(true));
+    }
+}
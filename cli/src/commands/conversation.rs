@@ -0,0 +1,23 @@
+use anyhow::Result;
+
+use crate::github::client::GitHubClient;
+use crate::github::types::ConversationResponse;
+
+pub async fn run(url: &str) -> Result<()> {
+    let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
+
+    let (issue_comments, reviews) = tokio::try_join!(
+        client.get_issue_comments(&pr_ref),
+        client.get_reviews(&pr_ref)
+    )?;
+
+    let response = ConversationResponse {
+        issue_comments,
+        reviews,
+    };
+
+    println!("{}", serde_json::to_string(&response)?);
+
+    Ok(())
+}
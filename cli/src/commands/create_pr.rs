@@ -0,0 +1,284 @@
+use anyhow::{Result, anyhow};
+use std::process::Command;
+
+use crate::commands::fetch::{self, FetchOpts, parse_remote_url};
+use crate::github::client::GitHubClient;
+use crate::github::types::{DEFAULT_HOST, PrRef};
+
+#[derive(Debug, Clone, Default)]
+pub struct CreatePrOpts {
+    /// Branch to merge into; defaults to the repository's default branch
+    pub base: Option<String>,
+    pub title: String,
+    pub body: Option<String>,
+    pub draft: bool,
+    /// `[HOST/]OWNER/REPO`; defaults to the repository `origin` points at
+    pub repo: Option<String>,
+    /// Remote to push the branch to; defaults to the one `git push` would use
+    pub remote: Option<String>,
+}
+
+pub async fn run(opts: CreatePrOpts) -> Result<()> {
+    let (host, owner, repo) = match opts.repo.as_deref() {
+        Some(repo) => parse_repo_arg(repo)?,
+        None => origin_repo()?,
+    };
+    let client = GitHubClient::new(&host)?;
+
+    let branch = current_branch()?;
+    let remote = match opts.remote {
+        Some(remote) => remote,
+        None => push_remote(&branch)?,
+    };
+    let pushed_to = remote_repo(&remote)?;
+    let head = pr_head((&host, &owner, &repo), &pushed_to, &branch)?;
+    let fork = head != branch;
+
+    // Everything that can fail without side effects is checked before pushing.
+    let base = match opts.base {
+        Some(base) => {
+            if !client.branch_exists(&owner, &repo, &base).await? {
+                return Err(anyhow!(
+                    "Base branch '{}' does not exist in {}/{}",
+                    base,
+                    owner,
+                    repo
+                ));
+            }
+            base
+        }
+        None => client.get_default_branch(&owner, &repo).await?,
+    };
+    if !fork && branch == base {
+        return Err(anyhow!(
+            "Current branch is the base branch '{}'; check out a feature branch first",
+            base
+        ));
+    }
+
+    let qualified_head = format!("{}:{}", pushed_to.1, branch);
+    if let Some(number) = client.find_open_pr(&owner, &repo, &qualified_head).await? {
+        let existing = PrRef {
+            host,
+            owner,
+            repo,
+            number,
+        };
+        return Err(anyhow!(
+            "A PR from {} is already open: {}",
+            qualified_head,
+            existing.url()
+        ));
+    }
+
+    push_branch(&remote, &branch)?;
+
+    let number = client
+        .create_pr(
+            &owner,
+            &repo,
+            &head,
+            &base,
+            &opts.title,
+            opts.body.as_deref(),
+            opts.draft,
+        )
+        .await?;
+
+    let pr_ref = PrRef {
+        host,
+        owner,
+        repo,
+        number,
+    };
+    fetch::run(&pr_ref.url(), FetchOpts::default()).await
+}
+
+/// The `head` the pulls API expects: the bare branch when it was pushed to
+/// the target repository, `OWNER:BRANCH` when it was pushed to a fork
+fn pr_head(
+    target: (&str, &str, &str),
+    pushed_to: &(String, String, String),
+    branch: &str,
+) -> Result<String> {
+    let (host, owner, repo) = target;
+    if pushed_to.0 != host {
+        return Err(anyhow!(
+            "Push remote is on {} but the PR targets {}; a PR can only be opened from the same host",
+            pushed_to.0,
+            host
+        ));
+    }
+
+    if pushed_to.1.eq_ignore_ascii_case(owner) && pushed_to.2.eq_ignore_ascii_case(repo) {
+        Ok(branch.to_string())
+    } else {
+        Ok(format!("{}:{}", pushed_to.1, branch))
+    }
+}
+
+/// Parse `OWNER/REPO` or `HOST/OWNER/REPO`
+fn parse_repo_arg(value: &str) -> Result<(String, String, String)> {
+    let parts: Vec<&str> = value.trim_matches('/').split('/').collect();
+    match parts.as_slice() {
+        [owner, repo] if !owner.is_empty() && !repo.is_empty() => Ok((
+            DEFAULT_HOST.to_string(),
+            owner.to_string(),
+            repo.to_string(),
+        )),
+        [host, owner, repo] if !host.is_empty() && !owner.is_empty() && !repo.is_empty() => {
+            Ok((host.to_lowercase(), owner.to_string(), repo.to_string()))
+        }
+        _ => Err(anyhow!(
+            "Invalid repository '{}'. Expected [HOST/]OWNER/REPO",
+            value
+        )),
+    }
+}
+
+fn origin_repo() -> Result<(String, String, String)> {
+    remote_repo("origin").map_err(|e| anyhow!("{}; pass --repo [HOST/]OWNER/REPO", e))
+}
+
+/// The GitHub repository a remote points at
+fn remote_repo(remote: &str) -> Result<(String, String, String)> {
+    let output = Command::new("git")
+        .args(["remote", "get-url", remote])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(anyhow!("No '{}' remote found", remote));
+    }
+
+    let remote_url = String::from_utf8(output.stdout)?;
+    parse_remote_url(remote_url.trim()).ok_or_else(|| {
+        anyhow!(
+            "Remote '{}' ({}) is not a GitHub repository",
+            remote,
+            remote_url.trim()
+        )
+    })
+}
+
+/// The remote `git push` would pick for the branch: `branch.<name>.pushRemote`,
+/// then `remote.pushDefault`, then `branch.<name>.remote`, then `origin`
+fn push_remote(branch: &str) -> Result<String> {
+    let keys = [
+        format!("branch.{}.pushRemote", branch),
+        "remote.pushDefault".to_string(),
+        format!("branch.{}.remote", branch),
+    ];
+    for key in &keys {
+        let output = Command::new("git")
+            .args(["config", "--get", key])
+            .output()?;
+        let value = String::from_utf8(output.stdout)?.trim().to_string();
+        // `branch.<name>.remote` is `.` for branches tracking a local branch.
+        if output.status.success() && !value.is_empty() && value != "." {
+            return Ok(value);
+        }
+    }
+
+    Ok("origin".to_string())
+}
+
+fn current_branch() -> Result<String> {
+    let output = Command::new("git")
+        .args(["symbolic-ref", "--quiet", "--short", "HEAD"])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "HEAD is detached; check out the branch to open a PR from"
+        ));
+    }
+
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+fn push_branch(remote: &str, branch: &str) -> Result<()> {
+    let output = Command::new("git")
+        .args(["push", "--set-upstream", remote, branch])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "git push --set-upstream {} {} failed: {}",
+            remote,
+            branch,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_repo_arg_defaults_host() {
+        assert_eq!(
+            parse_repo_arg("owner/repo").unwrap(),
+            (
+                "github.com".to_string(),
+                "owner".to_string(),
+                "repo".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_repo_arg_accepts_enterprise_host() {
+        assert_eq!(
+            parse_repo_arg("GHE.example.com/team/service").unwrap(),
+            (
+                "ghe.example.com".to_string(),
+                "team".to_string(),
+                "service".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_repo_arg_rejects_malformed_values() {
+        assert!(parse_repo_arg("repo").is_err());
+        assert!(parse_repo_arg("owner/").is_err());
+        assert!(parse_repo_arg("a/b/c/d").is_err());
+    }
+
+    fn repo(host: &str, owner: &str, name: &str) -> (String, String, String) {
+        (host.to_string(), owner.to_string(), name.to_string())
+    }
+
+    #[test]
+    fn pr_head_is_bare_branch_for_the_target_repo() {
+        let head = pr_head(
+            ("github.com", "owner", "repo"),
+            &repo("github.com", "Owner", "Repo"),
+            "feature",
+        );
+        assert_eq!(head.unwrap(), "feature");
+    }
+
+    #[test]
+    fn pr_head_is_owner_qualified_for_a_fork() {
+        let head = pr_head(
+            ("github.com", "owner", "repo"),
+            &repo("github.com", "me", "repo"),
+            "feature",
+        );
+        assert_eq!(head.unwrap(), "me:feature");
+    }
+
+    #[test]
+    fn pr_head_rejects_a_remote_on_another_host() {
+        let head = pr_head(
+            ("github.com", "owner", "repo"),
+            &repo("ghe.example.com", "owner", "repo"),
+            "feature",
+        );
+        assert!(head.is_err());
+    }
+}
@@ -1,14 +1,18 @@
 use anyhow::{Result, anyhow};
 use regex::Regex;
-use serde::Serialize;
-use std::process::Command;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::process::{Command, Stdio};
 
 use crate::diff::parser::parse_patch;
-use crate::github::types::{FileStatus, ReviewFile};
+use crate::github::types::{FileStatus, Relocation, RelocationStatus, ReviewFile};
 
 const IGNORE_WHITESPACE_CHANGES: bool = true;
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
 pub struct LocalDiffCliOpts {
     pub target: Option<String>,
     pub cached_only: bool,
@@ -31,11 +35,15 @@ pub struct DiffResponse {
 }
 
 pub fn run(opts: LocalDiffCliOpts) -> Result<()> {
-    let response = get_local_diff(opts, IGNORE_WHITESPACE_CHANGES)?;
+    let response = local_diff(opts)?;
     println!("{}", serde_json::to_string(&response)?);
     Ok(())
 }
 
+pub(crate) fn local_diff(opts: LocalDiffCliOpts) -> Result<DiffResponse> {
+    get_local_diff(opts, IGNORE_WHITESPACE_CHANGES)
+}
+
 pub(crate) fn get_local_diff(
     opts: LocalDiffCliOpts,
     ignore_whitespace: bool,
@@ -73,6 +81,351 @@ pub(crate) fn get_pr_review_files(
     parse_git_diff(&diff_output)
 }
 
+/// Review files for a single commit against its (first) parent, or against
+/// the empty tree for a root commit
+pub(crate) fn get_commit_review_files(
+    commit_sha: &str,
+    ignore_whitespace: bool,
+) -> Result<Vec<ReviewFile>> {
+    let parent = format!("{commit_sha}^");
+    let has_parent = Command::new("git")
+        .args([
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{parent}^{{commit}}"),
+        ])
+        .output()?
+        .status
+        .success();
+
+    if has_parent {
+        get_pr_review_files(&parent, commit_sha, ignore_whitespace)
+    } else {
+        get_review_files_between(&empty_tree()?, commit_sha, ignore_whitespace)
+    }
+}
+
+/// ID of the empty tree in this repository's hash format
+fn empty_tree() -> Result<String> {
+    let output = Command::new("git")
+        .args(["hash-object", "-t", "tree", "/dev/null"])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Failed to hash the empty tree: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+/// Review files for the changes between two revisions or trees (two-dot)
+pub(crate) fn get_review_files_between(
+    from: &str,
+    to: &str,
+    ignore_whitespace: bool,
+) -> Result<Vec<ReviewFile>> {
+    let mut args = vec!["diff"];
+    if ignore_whitespace {
+        args.push("-w");
+    }
+    args.extend([from, to]);
+
+    let output = Command::new("git").args(&args).output()?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Failed to get git diff (git {}): {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    let diff_output = String::from_utf8(output.stdout)?;
+    if diff_output.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    parse_git_diff(&diff_output)
+}
+
+/// Resolve a (possibly abbreviated) revision to a full commit SHA
+pub(crate) fn resolve_commit(rev: &str) -> Result<String> {
+    let object = format!("{rev}^{{commit}}");
+    let output = Command::new("git")
+        .args(["rev-parse", "--verify", "--quiet", &object])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(anyhow!("Git commit {rev} is not available locally"));
+    }
+
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+pub(crate) fn merge_base(a: &str, b: &str) -> Result<String> {
+    let output = Command::new("git").args(["merge-base", a, b]).output()?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Failed to compute merge-base of {a} and {b}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+pub(crate) fn is_ancestor(ancestor: &str, descendant: &str) -> Result<bool> {
+    let output = Command::new("git")
+        .args(["merge-base", "--is-ancestor", ancestor, descendant])
+        .output()?;
+
+    match output.status.code() {
+        Some(0) => Ok(true),
+        Some(1) => Ok(false),
+        _ => Err(anyhow!(
+            "Failed to check whether {ancestor} is an ancestor of {descendant}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        )),
+    }
+}
+
+/// Replay the changes `old_base..old_head` onto `new_base` and return the
+/// resulting tree, like cherry-picking a rebased branch, without touching the
+/// work tree or the real index. Returns None when the changes don't apply.
+pub(crate) fn replay_onto(
+    old_base: &str,
+    old_head: &str,
+    new_base: &str,
+) -> Result<Option<String>> {
+    if old_base == new_base {
+        return Ok(Some(format!("{old_head}^{{tree}}")));
+    }
+
+    let index =
+        std::env::temp_dir().join(format!("neo-reviewer-replay-{}.index", std::process::id()));
+    let result = replay_with_index(&index, old_base, old_head, new_base);
+    let _ = std::fs::remove_file(&index);
+    result
+}
+
+fn replay_with_index(
+    index: &Path,
+    old_base: &str,
+    old_head: &str,
+    new_base: &str,
+) -> Result<Option<String>> {
+    let git = |args: &[&str]| {
+        let mut command = Command::new("git");
+        command.env("GIT_INDEX_FILE", index).args(args);
+        command
+    };
+    let run = |args: &[&str]| -> Result<Vec<u8>> {
+        let output = git(args).output()?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        Ok(output.stdout)
+    };
+
+    run(&["read-tree", new_base])?;
+    let patch = run(&[
+        "diff",
+        "--no-ext-diff",
+        "--binary",
+        "--full-index",
+        old_base,
+        old_head,
+    ])?;
+
+    if !patch.is_empty() {
+        let mut apply = git(&["apply", "--cached", "--3way"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        if let Some(mut stdin) = apply.stdin.take() {
+            stdin.write_all(&patch)?;
+        }
+        if !apply.wait()?.success() {
+            return Ok(None);
+        }
+    }
+
+    let tree = run(&["write-tree"])?;
+    Ok(Some(String::from_utf8(tree)?.trim().to_string()))
+}
+
+/// Map a line of `path` at revision `from` to the same line at revision `to`.
+/// Returns None when the line itself was changed in between.
+pub(crate) fn map_line_between_revisions(
+    from: &str,
+    to: &str,
+    path: &str,
+    line: u32,
+) -> Result<Option<u32>> {
+    let relocation = relocate_line_between_revisions(from, to, path, line)?;
+    Ok(unchanged_line(relocation))
+}
+
+/// Follow a line of `path` at revision `from` to revision `to`, reporting the
+/// closest line when it was edited and `Gone` when it was deleted.
+pub(crate) fn relocate_line_between_revisions(
+    from: &str,
+    to: &str,
+    path: &str,
+    line: u32,
+) -> Result<Relocation> {
+    relocate_line_in(Path::new("."), from, to, path, line)
+}
+
+fn relocate_line_in(
+    repo: &Path,
+    from: &str,
+    to: &str,
+    path: &str,
+    line: u32,
+) -> Result<Relocation> {
+    let git_diff = |args: &[&str]| -> Result<String> {
+        let output = Command::new("git").args(args).current_dir(repo).output()?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to diff {path} between {from} and {to}: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+        Ok(String::from_utf8(output.stdout)?)
+    };
+
+    // A single-path diff can't see renames, so look up the file's name at `to`
+    // first and diff both names together.
+    let name_status = git_diff(&["diff", "-M", "--name-status", "-z", from, to])?;
+    let mut args = vec!["diff", "-U0", "-M", "--no-color", from, to, "--", path];
+    if let Some(renamed) = renamed_to(&name_status, path) {
+        args.push(renamed);
+    }
+
+    relocate_line_through_hunks(&git_diff(&args)?, line)
+}
+
+/// New name of `path` in `git diff --name-status -z` output, if it was renamed
+fn renamed_to<'a>(name_status: &'a str, path: &str) -> Option<&'a str> {
+    let mut fields = name_status.split('\0');
+    while let Some(status) = fields.next() {
+        if status.starts_with('R') || status.starts_with('C') {
+            let (old, new) = (fields.next()?, fields.next()?);
+            if status.starts_with('R') && old == path {
+                return Some(new);
+            }
+        } else {
+            fields.next()?;
+        }
+    }
+    None
+}
+
+/// New-side line ranges of the hunks in the PR diff of `path`. Uses git's
+/// default context, like the diff GitHub anchors review comments to.
+pub(crate) fn get_pr_file_hunks(
+    base_sha: &str,
+    head_sha: &str,
+    path: &str,
+) -> Result<Vec<RangeInclusive<u32>>> {
+    let diff_target = build_pr_diff_target(base_sha, head_sha);
+    let output = Command::new("git")
+        .args(["diff", "--no-color", &diff_target, "--", path])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Failed to diff {path} for {diff_target}: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    new_side_hunks(&String::from_utf8(output.stdout)?)
+}
+
+/// New-side line ranges of each hunk header, skipping hunks that only delete
+pub(crate) fn new_side_hunks(diff_output: &str) -> Result<Vec<RangeInclusive<u32>>> {
+    let hunk_re = Regex::new(r"^@@ -\d+(?:,\d+)? \+(\d+)(?:,(\d+))? @@")?;
+
+    Ok(diff_output
+        .lines()
+        .filter_map(|l| hunk_re.captures(l))
+        .filter_map(|caps| {
+            let start: u32 = caps[1].parse().ok()?;
+            let count: u32 = caps.get(2).map_or(Some(1), |m| m.as_str().parse().ok())?;
+            (count > 0).then(|| start..=start + count - 1)
+        })
+        .collect())
+}
+
+fn unchanged_line(relocation: Relocation) -> Option<u32> {
+    match relocation.status {
+        RelocationStatus::Moved => relocation.line,
+        RelocationStatus::Changed | RelocationStatus::Gone => None,
+    }
+}
+
+fn relocate_line_through_hunks(diff_output: &str, line: u32) -> Result<Relocation> {
+    let hunk_re = Regex::new(r"^@@ -(\d+)(?:,(\d+))? \+(\d+)(?:,(\d+))? @@")?;
+    let mut offset: i64 = 0;
+
+    for caps in diff_output.lines().filter_map(|l| hunk_re.captures(l)) {
+        let number = |i: usize| -> u32 {
+            caps.get(i)
+                .and_then(|m| m.as_str().parse().ok())
+                .unwrap_or(1)
+        };
+        let (old_start, old_count, new_start, new_count) =
+            (number(1), number(2), number(3), number(4));
+
+        if old_count > 0 && (old_start..old_start + old_count).contains(&line) {
+            if new_count == 0 {
+                return Ok(Relocation {
+                    status: RelocationStatus::Gone,
+                    line: None,
+                });
+            }
+            // Keep the line's position within the hunk, clamped to the replacement.
+            let within = (line - old_start).min(new_count - 1);
+            return Ok(Relocation {
+                status: RelocationStatus::Changed,
+                line: Some(new_start + within),
+            });
+        }
+
+        // A zero-length old range is an insertion after `old_start`.
+        let old_end = if old_count == 0 {
+            old_start
+        } else {
+            old_start + old_count - 1
+        };
+        if old_end >= line {
+            break;
+        }
+        offset += i64::from(new_count) - i64::from(old_count);
+    }
+
+    let line = u32::try_from(i64::from(line) + offset).ok();
+    Ok(Relocation {
+        status: if line.is_some() {
+            RelocationStatus::Moved
+        } else {
+            RelocationStatus::Gone
+        },
+        line,
+    })
+}
+
 pub(crate) fn get_git_root() -> Result<String> {
     let output = Command::new("git")
         .args(["rev-parse", "--show-toplevel"])
@@ -196,18 +549,7 @@ fn should_include_untracked(mode: LocalDiffMode, tracked_only: bool) -> bool {
 }
 
 fn get_merge_base(target: &str) -> Result<String> {
-    let output = Command::new("git")
-        .args(["merge-base", "HEAD", target])
-        .output()?;
-
-    if !output.status.success() {
-        return Err(anyhow!(
-            "Failed to compute merge-base with {target}: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ));
-    }
-
-    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+    merge_base("HEAD", target)
 }
 
 fn get_untracked_review_files(ignore_whitespace: bool) -> Result<Vec<ReviewFile>> {
@@ -342,6 +684,8 @@ pub(crate) fn parse_git_diff(diff_output: &str) -> Result<Vec<ReviewFile>> {
                     deletions,
                     content: None, // Local diff doesn't need content, files are on disk
                     change_blocks,
+                    annotations: Vec::new(),
+                    viewed_state: None,
                 });
             }
         } else {
@@ -410,12 +754,165 @@ mod tests {
         assert!(!should_include_untracked(LocalDiffMode::CachedOnly, false));
     }
 
+    #[test]
+    fn test_relocate_line_through_hunks_shifts_past_insertions_and_deletions() {
+        let diff = "\
+diff --git a/f b/f
+@@ -2,0 +3,2 @@
++new
++new
+@@ -10,3 +12 @@
+-old
+-old
+-old
++new";
+
+        let moved = |line| Relocation {
+            status: RelocationStatus::Moved,
+            line: Some(line),
+        };
+        let relocate = |line| relocate_line_through_hunks(diff, line).unwrap();
+        assert_eq!(relocate(1), moved(1));
+        assert_eq!(relocate(2), moved(2));
+        assert_eq!(relocate(3), moved(5));
+        assert_eq!(relocate(13), moved(13));
+        assert_eq!(
+            relocate(11),
+            Relocation {
+                status: RelocationStatus::Changed,
+                line: Some(12)
+            }
+        );
+    }
+
+    #[test]
+    fn test_relocate_line_through_hunks_reports_changed_and_gone_lines() {
+        let diff = "\
+diff --git a/f b/f
+@@ -4,2 +4,3 @@
+-old
+-old
++new
++new
++new
+@@ -10,3 +11,0 @@
+-old
+-old
+-old";
+
+        let relocate = |line| relocate_line_through_hunks(diff, line).unwrap();
+        assert_eq!(
+            relocate(5),
+            Relocation {
+                status: RelocationStatus::Changed,
+                line: Some(5)
+            }
+        );
+        assert_eq!(
+            relocate(11),
+            Relocation {
+                status: RelocationStatus::Gone,
+                line: None
+            }
+        );
+        assert_eq!(
+            relocate(14),
+            Relocation {
+                status: RelocationStatus::Moved,
+                line: Some(12)
+            }
+        );
+    }
+
+    #[test]
+    fn test_relocate_line_through_hunks_without_changes_is_identity() {
+        assert_eq!(
+            relocate_line_through_hunks("", 42).unwrap(),
+            Relocation {
+                status: RelocationStatus::Moved,
+                line: Some(42)
+            }
+        );
+    }
+
+    #[test]
+    fn test_new_side_hunks_skips_deletion_only_hunks() {
+        let diff = "\
+diff --git a/a.rs b/a.rs
+--- a/a.rs
++++ b/a.rs
+@@ -1,3 +1,4 @@ fn main() {
+ one
++two
+ three
+ four
+@@ -10,2 +10,0 @@
+-gone
+-also gone
+@@ -20 +19 @@
+-old
++new";
+
+        assert_eq!(new_side_hunks(diff).unwrap(), vec![1..=4, 19..=19]);
+    }
+
     #[test]
     fn test_build_pr_diff_target_uses_three_dot_notation() {
         let target = build_pr_diff_target("base123", "head456");
         assert_eq!(target, "base123...head456");
     }
 
+    #[test]
+    fn test_renamed_to_finds_new_name() {
+        let name_status = "M\0src/a.rs\0R087\0src/old.rs\0src/new.rs\0D\0src/gone.rs\0";
+        assert_eq!(renamed_to(name_status, "src/old.rs"), Some("src/new.rs"));
+        assert_eq!(renamed_to(name_status, "src/a.rs"), None);
+        assert_eq!(renamed_to(name_status, "src/gone.rs"), None);
+    }
+
+    #[test]
+    fn test_relocate_line_follows_renamed_file() {
+        let dir = std::env::temp_dir().join(format!("neo-reviewer-rename-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(&dir)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {args:?} failed");
+            String::from_utf8(output.stdout).unwrap().trim().to_string()
+        };
+        let lines: Vec<String> = (1..=10).map(|i| format!("line {i}")).collect();
+
+        git(&["init", "--quiet"]);
+        std::fs::write(dir.join("old.rs"), lines.join("\n") + "\n").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "--quiet", "-m", "add"]);
+        let from = git(&["rev-parse", "HEAD"]);
+        git(&["mv", "old.rs", "new.rs"]);
+        std::fs::write(
+            dir.join("new.rs"),
+            format!("line 0\n{}\n", lines.join("\n")),
+        )
+        .unwrap();
+        git(&["commit", "--quiet", "-am", "rename"]);
+        let to = git(&["rev-parse", "HEAD"]);
+
+        let relocation = relocate_line_in(&dir, &from, &to, "old.rs", 5).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(
+            relocation,
+            Relocation {
+                status: RelocationStatus::Moved,
+                line: Some(6),
+            }
+        );
+    }
+
     #[test]
     fn test_ensure_git_commit_available_errors_for_unknown_commit() {
         let result = ensure_git_commit_available("definitely-not-a-real-commit");
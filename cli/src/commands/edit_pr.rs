@@ -0,0 +1,61 @@
+use anyhow::Result;
+
+use crate::github::client::GitHubClient;
+use crate::github::types::{EditPrResponse, PrRef, PullRequest};
+
+#[derive(Debug, Clone, Default)]
+pub struct EditPrOpts {
+    pub title: Option<String>,
+    pub body: Option<String>,
+    pub add_labels: Vec<String>,
+    pub remove_labels: Vec<String>,
+    /// `Some(true)` converts to draft, `Some(false)` marks ready for review
+    pub draft: Option<bool>,
+}
+
+pub async fn run(url: &str, opts: EditPrOpts) -> Result<()> {
+    let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
+
+    let response = match apply_edits(&client, &pr_ref, &opts).await {
+        Ok(pr) => EditPrResponse {
+            success: true,
+            pr: Some(pr),
+            error: None,
+        },
+        Err(e) => EditPrResponse {
+            success: false,
+            pr: None,
+            error: Some(e.to_string()),
+        },
+    };
+
+    println!("{}", serde_json::to_string(&response)?);
+    Ok(())
+}
+
+async fn apply_edits(
+    client: &GitHubClient,
+    pr_ref: &PrRef,
+    opts: &EditPrOpts,
+) -> Result<PullRequest> {
+    if opts.title.is_some() || opts.body.is_some() {
+        client
+            .update_pr(pr_ref, opts.title.as_deref(), opts.body.as_deref())
+            .await?;
+    }
+
+    if !opts.add_labels.is_empty() {
+        client.add_labels(pr_ref, &opts.add_labels).await?;
+    }
+
+    for label in &opts.remove_labels {
+        client.remove_label(pr_ref, label).await?;
+    }
+
+    if let Some(draft) = opts.draft {
+        client.set_pr_draft(pr_ref, draft).await?;
+    }
+
+    client.get_pr(pr_ref).await
+}
@@ -1,42 +1,335 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
 use std::process::Command;
 
-use crate::commands::diff::{ensure_git_commit_available, get_pr_review_files};
+use crate::commands::comments::{locate_comments, relocate_outdated_comments};
+use crate::commands::diff::{
+    ensure_git_commit_available, get_commit_review_files, get_pr_review_files,
+    get_review_files_between, is_ancestor, merge_base, replay_onto, resolve_commit,
+};
 use crate::github::client::GitHubClient;
-use crate::github::types::{FetchResponse, PrRef};
+use crate::github::types::{
+    Annotation, ChecksSummary, FetchResponse, Interdiff, PrCommit, PrRef, PullRequest,
+    ReviewComment, ReviewFile, ReviewThread, SubmittedReview,
+};
 
-pub async fn run(url: &str, skip_comments: bool) -> Result<()> {
-    let client = GitHubClient::new()?;
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct FetchOpts {
+    pub skip_comments: bool,
+    /// Review a single PR commit against its parent
+    pub commit: Option<String>,
+    /// Only show changes made since this commit
+    pub since: Option<String>,
+    /// Only show changes made since the viewer's latest submitted review
+    pub since_last_review: bool,
+}
+
+pub async fn run(url: &str, opts: FetchOpts) -> Result<()> {
     let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
+
+    let response = fetch(&client, &pr_ref, opts, &mut DiffCache::new()).await?;
+
+    // Output as JSON for Neovim consumption
+    println!("{}", serde_json::to_string(&response)?);
+
+    Ok(())
+}
 
-    // Fetch PR metadata and viewer in parallel
-    let (pr, viewer) = tokio::try_join!(client.get_pr(&pr_ref), client.get_viewer())?;
-    let remote = detect_repo_remote(&pr_ref)?.unwrap_or_else(|| "origin".to_string());
+/// Parsed full-PR change blocks keyed by (base SHA, head SHA)
+pub(crate) type DiffCache = HashMap<(String, String), Vec<ReviewFile>>;
 
-    ensure_base_commit_available(&pr_ref, &remote, &pr.base_sha, &pr.base_ref)?;
-    ensure_head_commit_available(&pr_ref, &remote, pr.number, &pr.head_sha, &pr.head_ref)?;
+/// Build the `fetch` response. Full-PR diffs are reused from `diff_cache`
+/// while the base and head are unchanged.
+pub(crate) async fn fetch(
+    client: &GitHubClient,
+    pr_ref: &PrRef,
+    opts: FetchOpts,
+    diff_cache: &mut DiffCache,
+) -> Result<FetchResponse> {
+    // Fetch PR metadata, commits and viewer in parallel
+    let (pr, commits, viewer) = tokio::try_join!(
+        client.get_pr(pr_ref),
+        client.get_pr_commits(pr_ref),
+        client.get_viewer()
+    )?;
+    ensure_pr_commits_available(pr_ref, &pr)?;
 
-    // Fetch change blocks from local git using the PR commit range.
-    let files = get_pr_review_files(&pr.base_sha, &pr.head_sha, true)?;
+    let commit = opts
+        .commit
+        .as_deref()
+        .map(|sha| resolve_pr_commit(&commits, sha, pr.number))
+        .transpose()?;
+    // Reviews looked up for --since-last-review are reused for the response.
+    let mut fetched_reviews = None;
+    let since = match (opts.since, opts.since_last_review) {
+        (Some(sha), _) => Some(sha),
+        (None, true) => {
+            let reviews = client.get_reviews(pr_ref).await?;
+            let sha = last_reviewed_commit(&reviews, &viewer).ok_or_else(|| {
+                anyhow!(
+                    "No submitted review by {} on PR #{} to compare against",
+                    viewer,
+                    pr.number
+                )
+            })?;
+            fetched_reviews = Some(reviews);
+            Some(sha)
+        }
+        (None, false) => None,
+    };
 
-    let comments = if skip_comments {
-        Vec::new()
+    // Fetch change blocks from local git: the whole PR commit range, a single
+    // commit against its parent, or only what changed since a reviewed commit.
+    let (mut files, interdiff) = match (&commit, &since) {
+        (Some(sha), _) => (get_commit_review_files(sha, true)?, None),
+        (None, Some(since)) => {
+            let (files, interdiff) = get_interdiff(pr_ref, &pr, since)?;
+            (files, Some(interdiff))
+        }
+        (None, None) => {
+            let key = (pr.base_sha.clone(), pr.head_sha.clone());
+            let files = match diff_cache.get(&key) {
+                Some(files) => files.clone(),
+                None => {
+                    let files = get_pr_review_files(&pr.base_sha, &pr.head_sha, true)?;
+                    diff_cache.insert(key, files.clone());
+                    files
+                }
+            };
+            (files, None)
+        }
+    };
+
+    let discussion = async {
+        if opts.skip_comments {
+            Ok((Vec::new(), Vec::new(), Vec::new(), Vec::new()))
+        } else {
+            let reviews = async {
+                match fetched_reviews {
+                    Some(reviews) => Ok(reviews),
+                    None => client.get_reviews(pr_ref).await,
+                }
+            };
+            tokio::try_join!(
+                client.get_review_comments(pr_ref),
+                client.get_review_threads(pr_ref),
+                client.get_issue_comments(pr_ref),
+                reviews
+            )
+        }
+    };
+    // Checks and viewed state are extras: a token without checks:read (or a
+    // GHES without the API) still gets the diff and discussion.
+    let (discussion, checks, viewed_states) = tokio::join!(
+        discussion,
+        client.get_checks(pr_ref, &pr.head_sha),
+        client.get_viewed_states(pr_ref)
+    );
+    let (mut comments, mut threads, issue_comments, reviews) = discussion?;
+    let checks = optional(checks, "checks");
+    let viewed_states = optional(viewed_states, "viewed state").unwrap_or_default();
+
+    relocate_outdated_comments(
+        pr_ref,
+        comments
+            .iter_mut()
+            .chain(threads.iter_mut().flat_map(|t| t.comments.iter_mut())),
+        &pr.head_sha,
+    );
+    let full_diff = commit.is_none() && interdiff.is_none();
+    locate_discussion(&files, full_diff, &mut comments, &mut threads);
+
+    for file in &mut files {
+        file.viewed_state = viewed_states.get(&file.path).copied();
+    }
+
+    let annotations = match &checks {
+        Some(checks) => optional(
+            client.get_check_annotations(pr_ref, checks).await,
+            "check annotations",
+        )
+        .unwrap_or_default(),
+        None => Vec::new(),
+    };
+    // Annotation lines refer to the PR head, so they only line up with the full diff.
+    let unmatched_annotations = if commit.is_some() {
+        annotations
+            .into_iter()
+            .map(|a| Annotation {
+                outside_diff: true,
+                ..a
+            })
+            .collect()
     } else {
-        client.get_review_comments(&pr_ref).await?
+        attach_annotations(&mut files, annotations)
     };
 
-    let response = FetchResponse {
+    Ok(FetchResponse {
         pr,
+        commits,
+        commit,
+        interdiff,
         files,
         comments,
+        threads,
+        issue_comments,
+        reviews,
+        checks: checks.map(ChecksSummary::from_checks),
+        unmatched_annotations,
         viewer,
-    };
+    })
+}
 
-    // Output as JSON for Neovim consumption
-    println!("{}", serde_json::to_string(&response)?);
+/// Place review comments on the change blocks of `files`. Comment lines
+/// refer to the PR head, so this is only done for the full base..head diff;
+/// for a single commit or an interdiff the fields are left unset.
+fn locate_discussion(
+    files: &[ReviewFile],
+    full_diff: bool,
+    comments: &mut [ReviewComment],
+    threads: &mut [ReviewThread],
+) {
+    if !full_diff {
+        return;
+    }
+    locate_comments(
+        files,
+        comments
+            .iter_mut()
+            .chain(threads.iter_mut().flat_map(|t| t.comments.iter_mut())),
+    );
+}
 
-    Ok(())
+/// Keep going without a non-essential part of the response, warning on stderr
+pub(crate) fn optional<T>(result: Result<T>, what: &str) -> Option<T> {
+    match result {
+        Ok(value) => Some(value),
+        Err(e) => {
+            eprintln!("Warning: couldn't fetch {}: {}", what, e);
+            None
+        }
+    }
+}
+
+/// Commit the viewer's latest submitted review was left on
+fn last_reviewed_commit(reviews: &[SubmittedReview], viewer: &str) -> Option<String> {
+    reviews
+        .iter()
+        .rev()
+        .filter(|r| r.author == viewer)
+        .find_map(|r| r.commit_id.clone())
+}
+
+/// Diff the PR head against what was reviewed at `since`. The reviewed
+/// changes are first replayed onto the PR's current merge base (a range-diff
+/// style comparison), so a pure rebase onto a newer base shows no changes.
+/// If they no longer apply there, fall back to a direct diff.
+fn get_interdiff(
+    pr_ref: &PrRef,
+    pr: &PullRequest,
+    since: &str,
+) -> Result<(Vec<ReviewFile>, Interdiff)> {
+    ensure_commit_fetched(pr_ref, since)?;
+    let since_sha = resolve_commit(since)?;
+
+    let history_rewritten = !is_ancestor(&since_sha, &pr.head_sha)?;
+    let old_base = merge_base(&since_sha, &pr.base_sha)?;
+    let new_base = merge_base(&pr.head_sha, &pr.base_sha)?;
+
+    let replayed = replay_onto(&old_base, &since_sha, &new_base)?;
+    let replay_conflicted = replayed.is_none();
+    let from = replayed.unwrap_or_else(|| since_sha.clone());
+    let files = get_review_files_between(&from, &pr.head_sha, true)?;
+
+    Ok((
+        files,
+        Interdiff {
+            since_sha,
+            history_rewritten,
+            base_changed: old_base != new_base,
+            replay_conflicted,
+        },
+    ))
+}
+
+/// Fetch a previously reviewed commit that may no longer be on any branch
+/// (e.g. after a force-push). GitHub still serves such commits by SHA.
+pub(crate) fn ensure_commit_fetched(pr_ref: &PrRef, sha: &str) -> Result<()> {
+    if ensure_git_commit_available(sha).is_ok() {
+        return Ok(());
+    }
+
+    let remote = detect_repo_remote(&pr_ref.host, &pr_ref.owner, &pr_ref.repo)?
+        .unwrap_or_else(|| "origin".to_string());
+    let _ = fetch_remote_ref(&remote, sha);
+
+    ensure_git_commit_available(sha).map_err(|_| {
+        anyhow!(
+            "Missing commit {} locally after fetching it from remote '{}'. \
+Run `git fetch {} {}` and retry.",
+            sha,
+            remote,
+            remote,
+            sha
+        )
+    })
+}
+
+/// Find the PR commit matching a full or abbreviated SHA
+fn resolve_pr_commit(commits: &[PrCommit], sha: &str, pr_number: u64) -> Result<String> {
+    let sha = sha.to_lowercase();
+    let mut matches = commits.iter().filter(|c| c.sha.starts_with(&sha));
+
+    match (matches.next(), matches.next()) {
+        (Some(commit), None) if !sha.is_empty() => Ok(commit.sha.clone()),
+        (Some(_), Some(_)) => Err(anyhow!(
+            "Commit {} is ambiguous in PR #{}; use a longer SHA",
+            sha,
+            pr_number
+        )),
+        _ => Err(anyhow!("Commit {} is not part of PR #{}", sha, pr_number)),
+    }
+}
+
+/// Attach CI annotations to the change blocks they overlap. Annotations
+/// elsewhere in a changed file go on the file; those on files outside the
+/// PR are returned. Both of the latter are flagged `outside_diff`.
+fn attach_annotations(files: &mut [ReviewFile], annotations: Vec<Annotation>) -> Vec<Annotation> {
+    let mut unmatched = Vec::new();
+
+    for mut annotation in annotations {
+        let Some(file) = files.iter_mut().find(|f| f.path == annotation.path) else {
+            annotation.outside_diff = true;
+            unmatched.push(annotation);
+            continue;
+        };
+
+        let block = file.change_blocks.iter_mut().find(|block| {
+            annotation.start_line <= block.end_line && block.start_line <= annotation.end_line
+        });
+        match block {
+            Some(block) => block.annotations.push(annotation),
+            None => {
+                annotation.outside_diff = true;
+                file.annotations.push(annotation);
+            }
+        }
+    }
+
+    unmatched
+}
+
+/// Make sure the PR's base and head commits exist locally, fetching them if needed
+pub(crate) fn ensure_pr_commits_available(pr_ref: &PrRef, pr: &PullRequest) -> Result<()> {
+    let remote = detect_repo_remote(&pr_ref.host, &pr_ref.owner, &pr_ref.repo)?
+        .unwrap_or_else(|| "origin".to_string());
+
+    ensure_base_commit_available(pr_ref, &remote, &pr.base_sha, &pr.base_ref)?;
+    ensure_head_commit_available(pr_ref, &remote, pr.number, &pr.head_sha, &pr.head_ref)
 }
 
 fn ensure_base_commit_available(
@@ -117,7 +410,8 @@ fn fetch_remote_ref(remote: &str, git_ref: &str) -> Result<()> {
     Ok(())
 }
 
-fn detect_repo_remote(pr_ref: &PrRef) -> Result<Option<String>> {
+/// Find the git remote pointing at `owner/repo` on `host`
+pub(crate) fn detect_repo_remote(host: &str, owner: &str, repo: &str) -> Result<Option<String>> {
     let output = Command::new("git").args(["remote", "-v"]).output()?;
     if !output.status.success() {
         return Ok(None);
@@ -130,7 +424,7 @@ fn detect_repo_remote(pr_ref: &PrRef) -> Result<Option<String>> {
         if let Some(caps) = remote_line_re.captures(line) {
             let remote_name = caps[1].to_string();
             let remote_url = caps[2].to_string();
-            if remote_points_to_repo(&remote_url, &pr_ref.owner, &pr_ref.repo) {
+            if remote_points_to_repo(&remote_url, host, owner, repo) {
                 return Ok(Some(remote_name));
             }
         }
@@ -139,37 +433,39 @@ fn detect_repo_remote(pr_ref: &PrRef) -> Result<Option<String>> {
     Ok(None)
 }
 
-fn remote_points_to_repo(remote_url: &str, owner: &str, repo: &str) -> bool {
-    let ssh_re = Regex::new(r"^git@github\.com:([^/]+)/([^/]+?)(?:\.git)?$").ok();
-    let https_re = Regex::new(r"^https?://github\.com/([^/]+)/([^/]+?)(?:\.git)?$").ok();
+/// Split an SSH or HTTPS remote URL into (host, owner, repo)
+pub(crate) fn parse_remote_url(remote_url: &str) -> Option<(String, String, String)> {
+    let ssh_re = Regex::new(r"^(?:ssh://)?git@([^:/]+)[:/]([^/]+)/([^/]+?)(?:\.git)?/?$").ok()?;
+    let https_re =
+        Regex::new(r"^https?://(?:[^@/]+@)?([^/]+)/([^/]+)/([^/]+?)(?:\.git)?/?$").ok()?;
 
-    let mut parsed = None;
-    if let Some(re) = ssh_re
-        && let Some(caps) = re.captures(remote_url)
-    {
-        parsed = Some((caps[1].to_string(), caps[2].to_string()));
-    }
-    if parsed.is_none()
-        && let Some(re) = https_re
-        && let Some(caps) = re.captures(remote_url)
-    {
-        parsed = Some((caps[1].to_string(), caps[2].to_string()));
-    }
+    let caps = ssh_re
+        .captures(remote_url)
+        .or_else(|| https_re.captures(remote_url))?;
+    Some((
+        caps[1].to_lowercase(),
+        caps[2].to_string(),
+        caps[3].to_string(),
+    ))
+}
 
-    match parsed {
-        Some((remote_owner, remote_repo)) => remote_owner == owner && remote_repo == repo,
-        None => false,
-    }
+fn remote_points_to_repo(remote_url: &str, host: &str, owner: &str, repo: &str) -> bool {
+    parse_remote_url(remote_url).is_some_and(|(remote_host, remote_owner, remote_repo)| {
+        remote_host.eq_ignore_ascii_case(host) && remote_owner == owner && remote_repo == repo
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::diff::parser::parse_patch;
+    use crate::github::types::{FileStatus, LineKind};
 
     #[test]
     fn remote_points_to_repo_matches_https_url() {
         assert!(remote_points_to_repo(
             "https://github.com/owner/repo.git",
+            "github.com",
             "owner",
             "repo"
         ));
@@ -179,6 +475,7 @@ mod tests {
     fn remote_points_to_repo_matches_ssh_url() {
         assert!(remote_points_to_repo(
             "git@github.com:owner/repo.git",
+            "github.com",
             "owner",
             "repo"
         ));
@@ -188,6 +485,7 @@ mod tests {
     fn remote_points_to_repo_rejects_non_matching_repo() {
         assert!(!remote_points_to_repo(
             "https://github.com/owner/other.git",
+            "github.com",
             "owner",
             "repo"
         ));
@@ -197,8 +495,217 @@ mod tests {
     fn remote_points_to_repo_rejects_non_github_url() {
         assert!(!remote_points_to_repo(
             "https://gitlab.com/owner/repo.git",
+            "github.com",
+            "owner",
+            "repo"
+        ));
+    }
+
+    #[test]
+    fn remote_points_to_repo_matches_enterprise_ssh_url() {
+        assert!(remote_points_to_repo(
+            "git@ghe.example.com:owner/repo.git",
+            "ghe.example.com",
+            "owner",
+            "repo"
+        ));
+        assert!(remote_points_to_repo(
+            "ssh://git@ghe.example.com/owner/repo.git",
+            "ghe.example.com",
             "owner",
             "repo"
         ));
     }
+
+    #[test]
+    fn remote_points_to_repo_matches_enterprise_https_url() {
+        assert!(remote_points_to_repo(
+            "https://ghe.example.com/owner/repo",
+            "ghe.example.com",
+            "owner",
+            "repo"
+        ));
+    }
+
+    #[test]
+    fn remote_points_to_repo_rejects_same_repo_on_other_host() {
+        assert!(!remote_points_to_repo(
+            "git@github.com:owner/repo.git",
+            "ghe.example.com",
+            "owner",
+            "repo"
+        ));
+        assert!(!remote_points_to_repo(
+            "https://ghe.example.com.evil.test/owner/repo.git",
+            "ghe.example.com",
+            "owner",
+            "repo"
+        ));
+    }
+
+    #[test]
+    fn parse_remote_url_splits_ssh_and_https() {
+        assert_eq!(
+            parse_remote_url("git@GitHub.com:owner/repo.git"),
+            Some((
+                "github.com".to_string(),
+                "owner".to_string(),
+                "repo".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_remote_url("https://token@ghe.example.com/team/service"),
+            Some((
+                "ghe.example.com".to_string(),
+                "team".to_string(),
+                "service".to_string()
+            ))
+        );
+        assert_eq!(parse_remote_url("/srv/git/repo.git"), None);
+    }
+
+    fn review_file(path: &str, patch: &str) -> ReviewFile {
+        ReviewFile {
+            path: path.to_string(),
+            status: FileStatus::Modified,
+            additions: 0,
+            deletions: 0,
+            content: None,
+            change_blocks: parse_patch(patch),
+            annotations: Vec::new(),
+            viewed_state: None,
+        }
+    }
+
+    fn comment(path: &str, line: u32) -> ReviewComment {
+        ReviewComment {
+            id: 1,
+            path: path.to_string(),
+            line: Some(line),
+            side: "RIGHT".to_string(),
+            original_line: Some(line),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn locate_discussion_only_places_comments_on_the_full_diff() {
+        let files = vec![review_file("src/lib.rs", "@@ -1,1 +1,2 @@\n one\n+two")];
+        let thread = ReviewThread {
+            id: "T_1".to_string(),
+            path: "src/lib.rs".to_string(),
+            line: Some(1),
+            start_line: None,
+            side: "RIGHT".to_string(),
+            is_resolved: false,
+            is_outdated: false,
+            resolved_by: None,
+            comments: vec![comment("src/lib.rs", 1)],
+        };
+
+        let mut comments = vec![comment("src/lib.rs", 2)];
+        let mut threads = vec![thread.clone()];
+        locate_discussion(&files, false, &mut comments, &mut threads);
+        assert_eq!(comments[0].change_block, None);
+        assert_eq!(comments[0].line_kind, None);
+        assert_eq!(comments[0].outside_diff, None);
+        assert_eq!(threads[0].comments[0].outside_diff, None);
+
+        let mut threads = vec![thread];
+        locate_discussion(&files, true, &mut comments, &mut threads);
+        assert_eq!(comments[0].change_block, Some(0));
+        assert_eq!(comments[0].line_kind, Some(LineKind::Added));
+        assert_eq!(comments[0].outside_diff, Some(false));
+        assert_eq!(threads[0].comments[0].outside_diff, Some(true));
+    }
+
+    fn annotation(path: &str, start_line: u32, end_line: u32) -> Annotation {
+        Annotation {
+            check_name: "lint".to_string(),
+            path: path.to_string(),
+            start_line,
+            end_line,
+            level: "warning".to_string(),
+            title: None,
+            message: "unused variable".to_string(),
+            outside_diff: false,
+        }
+    }
+
+    fn pr_commit(sha: &str) -> PrCommit {
+        PrCommit {
+            sha: sha.to_string(),
+            message: "message".to_string(),
+            author: "author".to_string(),
+            authored_at: None,
+            verified: false,
+            verification_reason: None,
+        }
+    }
+
+    fn submitted_review(author: &str, commit_id: Option<&str>) -> SubmittedReview {
+        SubmittedReview {
+            id: 1,
+            author: author.to_string(),
+            state: "COMMENTED".to_string(),
+            submitted_at: None,
+            body: String::new(),
+            html_url: String::new(),
+            commit_id: commit_id.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn last_reviewed_commit_picks_viewers_latest_review() {
+        let reviews = vec![
+            submitted_review("me", Some("first")),
+            submitted_review("me", Some("second")),
+            submitted_review("someone", Some("third")),
+            submitted_review("me", None),
+        ];
+
+        assert_eq!(
+            last_reviewed_commit(&reviews, "me"),
+            Some("second".to_string())
+        );
+        assert_eq!(last_reviewed_commit(&reviews, "nobody"), None);
+    }
+
+    #[test]
+    fn resolve_pr_commit_accepts_abbreviated_sha() {
+        let commits = vec![pr_commit("abc1234def"), pr_commit("abd5678aaa")];
+
+        assert_eq!(
+            resolve_pr_commit(&commits, "ABC12", 1).unwrap(),
+            "abc1234def"
+        );
+        assert!(resolve_pr_commit(&commits, "ab", 1).is_err());
+        assert!(resolve_pr_commit(&commits, "fff", 1).is_err());
+        assert!(resolve_pr_commit(&commits, "", 1).is_err());
+    }
+
+    #[test]
+    fn attach_annotations_places_by_overlap() {
+        let mut files = vec![review_file(
+            "src/lib.rs",
+            "@@ -1,3 +1,4 @@\n line1\n+added\n line2\n line3",
+        )];
+
+        let unmatched = attach_annotations(
+            &mut files,
+            vec![
+                annotation("src/lib.rs", 1, 2),
+                annotation("src/lib.rs", 4, 4),
+                annotation("src/other.rs", 1, 1),
+            ],
+        );
+
+        let block = &files[0].change_blocks[0];
+        assert_eq!(block.annotations, vec![annotation("src/lib.rs", 1, 2)]);
+        assert_eq!(files[0].annotations.len(), 1);
+        assert!(files[0].annotations[0].outside_diff);
+        assert_eq!(unmatched.len(), 1);
+        assert_eq!(unmatched[0].path, "src/other.rs");
+        assert!(unmatched[0].outside_diff);
+    }
 }
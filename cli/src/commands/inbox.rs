@@ -0,0 +1,163 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+
+use crate::github::client::GitHubClient;
+use crate::github::http::{parse_utc, unix_now};
+use crate::github::types::{InboxItem, InboxReason, InboxResponse, SearchedPullRequest};
+
+const REVIEW_REQUESTED_QUERY: &str = "is:pr is:open archived:false review-requested:@me";
+const REVIEWED_QUERY: &str = "is:pr is:open archived:false reviewed-by:@me -author:@me";
+const AUTHORED_QUERY: &str = "is:pr is:open archived:false author:@me";
+
+pub async fn run(hostname: &str, limit: u32) -> Result<()> {
+    let client = GitHubClient::new(hostname)?;
+
+    let (requested, reviewed, authored) = tokio::try_join!(
+        client.search_pull_requests(REVIEW_REQUESTED_QUERY, limit),
+        client.search_pull_requests(REVIEWED_QUERY, limit),
+        client.search_pull_requests(AUTHORED_QUERY, limit),
+    )?;
+
+    let response = InboxResponse {
+        items: build_inbox(requested, reviewed, authored, unix_now()),
+    };
+
+    println!("{}", serde_json::to_string(&response)?);
+
+    Ok(())
+}
+
+/// Merge the three searches into one list, keeping only PRs that need
+/// attention. A PR matching several categories is listed once, under the
+/// first one (review requested, then new commits, then unresolved threads).
+fn build_inbox(
+    requested: Vec<SearchedPullRequest>,
+    reviewed: Vec<SearchedPullRequest>,
+    authored: Vec<SearchedPullRequest>,
+    now: u64,
+) -> Vec<InboxItem> {
+    let reviewed = reviewed.into_iter().filter(|pr| {
+        pr.viewer_reviewed_sha
+            .as_deref()
+            .is_some_and(|sha| sha != pr.head_sha)
+    });
+    let authored = authored.into_iter().filter(|pr| pr.unresolved_threads > 0);
+
+    let candidates = requested
+        .into_iter()
+        .map(|pr| (InboxReason::ReviewRequested, pr))
+        .chain(reviewed.map(|pr| (InboxReason::NewCommits, pr)))
+        .chain(authored.map(|pr| (InboxReason::UnresolvedThreads, pr)));
+
+    let mut seen = HashSet::new();
+    candidates
+        .filter(|(_, pr)| seen.insert(pr.url.clone()))
+        .map(|(reason, pr)| InboxItem {
+            reason,
+            age: parse_utc(&pr.created_at)
+                .map(|created| format_age(now.saturating_sub(created)))
+                .unwrap_or_default(),
+            pr,
+        })
+        .collect()
+}
+
+/// Format a duration in seconds as a compact age, e.g. `5m`, `3h`, `2d`, `6w`
+fn format_age(seconds: u64) -> String {
+    match seconds {
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86_400 => format!("{}h", s / 3600),
+        s if s < 7 * 86_400 => format!("{}d", s / 86_400),
+        s => format!("{}w", s / (7 * 86_400)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pr(number: u64, reviewed_sha: Option<&str>, unresolved_threads: u32) -> SearchedPullRequest {
+        SearchedPullRequest {
+            repo: "owner/repo".to_string(),
+            number,
+            title: format!("PR {number}"),
+            url: format!("https://github.com/owner/repo/pull/{number}"),
+            author: "someone".to_string(),
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            is_draft: false,
+            additions: 10,
+            deletions: 2,
+            head_sha: "head".to_string(),
+            ci_state: Some("success".to_string()),
+            viewer_reviewed_sha: reviewed_sha.map(str::to_string),
+            unresolved_threads,
+        }
+    }
+
+    #[test]
+    fn keeps_reviewed_prs_only_when_head_moved() {
+        let items = build_inbox(
+            Vec::new(),
+            vec![
+                pr(1, Some("head"), 0),
+                pr(2, Some("old"), 0),
+                pr(3, None, 0),
+            ],
+            Vec::new(),
+            0,
+        );
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].pr.number, 2);
+        assert_eq!(items[0].reason, InboxReason::NewCommits);
+    }
+
+    #[test]
+    fn keeps_authored_prs_only_with_unresolved_threads() {
+        let items = build_inbox(
+            Vec::new(),
+            Vec::new(),
+            vec![pr(1, None, 0), pr(2, None, 3)],
+            0,
+        );
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].pr.number, 2);
+        assert_eq!(items[0].reason, InboxReason::UnresolvedThreads);
+    }
+
+    #[test]
+    fn lists_each_pr_once_under_first_reason() {
+        let items = build_inbox(
+            vec![pr(1, None, 0)],
+            vec![pr(1, Some("old"), 0)],
+            Vec::new(),
+            0,
+        );
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].reason, InboxReason::ReviewRequested);
+    }
+
+    #[test]
+    fn computes_age_from_created_at() {
+        let created = parse_utc("2026-01-01T00:00:00Z").unwrap();
+        let items = build_inbox(
+            vec![pr(1, None, 0)],
+            Vec::new(),
+            Vec::new(),
+            created + 2 * 86_400,
+        );
+
+        assert_eq!(items[0].age, "2d");
+    }
+
+    #[test]
+    fn formats_age_units() {
+        assert_eq!(format_age(59), "0m");
+        assert_eq!(format_age(2 * 3600), "2h");
+        assert_eq!(format_age(3 * 86_400), "3d");
+        assert_eq!(format_age(15 * 86_400), "2w");
+    }
+}
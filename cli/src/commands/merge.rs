@@ -0,0 +1,255 @@
+use anyhow::{Result, anyhow};
+use reqwest::StatusCode;
+
+use crate::github::client::GitHubClient;
+use crate::github::http::ApiError;
+use crate::github::types::{MergeBlocker, MergeMethod, MergeResponse, MergeStatus};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AutoMerge {
+    Enable,
+    Disable,
+}
+
+#[derive(Debug, Clone)]
+pub struct MergeOpts {
+    pub method: MergeMethod,
+    pub title: Option<String>,
+    pub body: Option<String>,
+    /// Head SHA (or a prefix of at least 7 characters) the caller reviewed;
+    /// the merge fails if the PR moved on. Required unless disabling auto-merge.
+    pub sha: Option<String>,
+    /// Toggle auto-merge instead of merging now
+    pub auto: Option<AutoMerge>,
+}
+
+pub async fn run(url: &str, opts: MergeOpts) -> Result<()> {
+    let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let expected_sha = opts.sha.as_deref().map(parse_expected_sha).transpose()?;
+    let client = GitHubClient::new(&pr_ref.host)?;
+
+    // Disabling auto-merge can't merge anything, so it needs no head guard.
+    if opts.auto == Some(AutoMerge::Disable) {
+        let status = client.get_merge_status(&pr_ref).await?;
+        let response = match client.disable_auto_merge(&status.node_id).await {
+            Ok(()) => auto_merge_toggled(false),
+            Err(e) => failure(Vec::new(), e.to_string()),
+        };
+        println!("{}", serde_json::to_string(&response)?);
+        return Ok(());
+    }
+
+    let Some(expected_sha) = expected_sha else {
+        return Err(anyhow!("--sha is required: pass the head SHA you reviewed"));
+    };
+
+    let status = client.get_merge_status(&pr_ref).await?;
+    if !status.head_sha.starts_with(&expected_sha) {
+        let response = failure(
+            vec![MergeBlocker::HeadChanged],
+            format!(
+                "PR head moved to {} since {}; review the new commits first",
+                status.head_sha, expected_sha
+            ),
+        );
+        println!("{}", serde_json::to_string(&response)?);
+        return Ok(());
+    }
+    // GitHub wants the full SHA. A prefix verified above names the same
+    // commit, so GitHub still refuses if the head moves before the merge.
+    let expected_sha = if expected_sha.len() == FULL_SHA_LEN {
+        expected_sha
+    } else {
+        status.head_sha.clone()
+    };
+
+    let title = opts.title.as_deref();
+    let body = opts.body.as_deref();
+
+    let response = if opts.auto == Some(AutoMerge::Enable) {
+        match client
+            .enable_auto_merge(&status.node_id, opts.method, &expected_sha, title, body)
+            .await
+        {
+            Ok(()) => auto_merge_toggled(true),
+            Err(e) => failure(blockers(&status), e.to_string()),
+        }
+    } else {
+        match client
+            .merge_pr(&pr_ref, opts.method, &expected_sha, title, body)
+            .await
+        {
+            Ok(merge_sha) => MergeResponse {
+                success: true,
+                merged: true,
+                merge_sha: Some(merge_sha),
+                auto_merge_enabled: None,
+                blockers: Vec::new(),
+                error: None,
+            },
+            Err(e) => {
+                // 409 means the head no longer matches the guard SHA.
+                let head_changed = e
+                    .downcast_ref::<ApiError>()
+                    .is_some_and(|api| api.status == StatusCode::CONFLICT);
+                let blockers = if head_changed {
+                    vec![MergeBlocker::HeadChanged]
+                } else {
+                    blockers(&status)
+                };
+                failure(blockers, e.to_string())
+            }
+        }
+    };
+
+    println!("{}", serde_json::to_string(&response)?);
+
+    Ok(())
+}
+
+const FULL_SHA_LEN: usize = 40;
+/// Shortest prefix accepted as a head guard, matching git's default abbreviation
+const MIN_SHA_LEN: usize = 7;
+
+/// Validate the reviewed head SHA, normalized to lowercase
+fn parse_expected_sha(value: &str) -> Result<String> {
+    let sha = value.trim().to_lowercase();
+    if !(MIN_SHA_LEN..=FULL_SHA_LEN).contains(&sha.len())
+        || !sha.chars().all(|c| c.is_ascii_hexdigit())
+    {
+        return Err(anyhow!(
+            "Invalid --sha '{}': expected {} to {} hex characters",
+            value,
+            MIN_SHA_LEN,
+            FULL_SHA_LEN
+        ));
+    }
+    Ok(sha)
+}
+
+fn auto_merge_toggled(enabled: bool) -> MergeResponse {
+    MergeResponse {
+        success: true,
+        merged: false,
+        merge_sha: None,
+        auto_merge_enabled: Some(enabled),
+        blockers: Vec::new(),
+        error: None,
+    }
+}
+
+fn failure(blockers: Vec<MergeBlocker>, error: String) -> MergeResponse {
+    MergeResponse {
+        success: false,
+        merged: false,
+        merge_sha: None,
+        auto_merge_enabled: None,
+        blockers,
+        error: Some(error),
+    }
+}
+
+/// Explain why GitHub would refuse the merge, from the PR's merge status
+fn blockers(status: &MergeStatus) -> Vec<MergeBlocker> {
+    let mut blockers = Vec::new();
+
+    if status.state != "OPEN" {
+        blockers.push(MergeBlocker::NotOpen);
+    }
+    if status.is_draft {
+        blockers.push(MergeBlocker::Draft);
+    }
+    if status.mergeable == "CONFLICTING" || status.merge_state_status == "DIRTY" {
+        blockers.push(MergeBlocker::Conflicts);
+    }
+    if status.merge_state_status == "BEHIND" {
+        blockers.push(MergeBlocker::BehindBase);
+    }
+    match status.review_decision.as_deref() {
+        Some("REVIEW_REQUIRED") => blockers.push(MergeBlocker::ReviewRequired),
+        Some("CHANGES_REQUESTED") => blockers.push(MergeBlocker::ChangesRequested),
+        _ => {}
+    }
+    // Checks only block when they're required; UNSTABLE means failing but optional.
+    if status.merge_state_status == "BLOCKED" {
+        match status.checks_state.as_deref() {
+            Some("FAILURE" | "ERROR") => blockers.push(MergeBlocker::ChecksFailing),
+            Some("PENDING" | "EXPECTED") => blockers.push(MergeBlocker::ChecksPending),
+            _ => {}
+        }
+    }
+
+    blockers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(merge_state_status: &str) -> MergeStatus {
+        MergeStatus {
+            node_id: "PR_1".to_string(),
+            head_sha: "abc".to_string(),
+            state: "OPEN".to_string(),
+            is_draft: false,
+            mergeable: "MERGEABLE".to_string(),
+            merge_state_status: merge_state_status.to_string(),
+            review_decision: None,
+            checks_state: Some("SUCCESS".to_string()),
+            auto_merge_enabled: false,
+        }
+    }
+
+    #[test]
+    fn parse_expected_sha_accepts_full_and_abbreviated_shas() {
+        let full = "0123456789abcdef0123456789abcdef01234567";
+        assert_eq!(parse_expected_sha(full).unwrap(), full);
+        assert_eq!(parse_expected_sha(" ABCDEF1 ").unwrap(), "abcdef1");
+    }
+
+    #[test]
+    fn parse_expected_sha_rejects_short_or_non_hex_values() {
+        assert!(parse_expected_sha("").is_err());
+        assert!(parse_expected_sha("abc").is_err());
+        assert!(parse_expected_sha("abcdef").is_err());
+        assert!(parse_expected_sha("main-branch").is_err());
+        assert!(parse_expected_sha(&"a".repeat(41)).is_err());
+    }
+
+    #[test]
+    fn clean_pr_has_no_blockers() {
+        assert!(blockers(&status("CLEAN")).is_empty());
+    }
+
+    #[test]
+    fn blocked_pr_reports_reviews_and_checks() {
+        let mut status = status("BLOCKED");
+        status.review_decision = Some("REVIEW_REQUIRED".to_string());
+        status.checks_state = Some("FAILURE".to_string());
+
+        assert_eq!(
+            blockers(&status),
+            vec![MergeBlocker::ReviewRequired, MergeBlocker::ChecksFailing]
+        );
+    }
+
+    #[test]
+    fn optional_failing_checks_do_not_block() {
+        let mut status = status("UNSTABLE");
+        status.checks_state = Some("FAILURE".to_string());
+
+        assert!(blockers(&status).is_empty());
+    }
+
+    #[test]
+    fn reports_conflicts_and_draft() {
+        let mut status = status("DIRTY");
+        status.is_draft = true;
+        status.mergeable = "CONFLICTING".to_string();
+
+        assert_eq!(
+            blockers(&status),
+            vec![MergeBlocker::Draft, MergeBlocker::Conflicts]
+        );
+    }
+}
@@ -1,7 +1,22 @@
 pub mod auth;
+pub mod cache;
+pub mod checks;
 pub mod comment;
 pub mod comments;
+pub mod conversation;
+pub mod create_pr;
 pub mod diff;
+pub mod edit_pr;
 pub mod fetch;
+pub mod inbox;
+pub mod merge;
+pub mod react;
 pub mod reply;
+pub mod review;
+pub mod reviewers;
+pub mod serve;
 pub mod submit;
+pub mod suggest;
+pub mod threads;
+pub mod viewed;
+pub mod watch;
@@ -0,0 +1,30 @@
+use anyhow::Result;
+
+use crate::github::client::GitHubClient;
+use crate::github::types::{ReactionContent, ReactionResponse};
+
+pub async fn run_set_reaction(
+    url: &str,
+    comment_id: u64,
+    content: ReactionContent,
+    reacted: bool,
+) -> Result<()> {
+    let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
+
+    let result = if reacted {
+        client.add_reaction(&pr_ref, comment_id, content).await
+    } else {
+        client.remove_reaction(&pr_ref, comment_id, content).await
+    };
+
+    let response = ReactionResponse {
+        success: result.is_ok(),
+        comment_id,
+        content,
+        error: result.err().map(|e| e.to_string()),
+    };
+    println!("{}", serde_json::to_string(&response)?);
+
+    Ok(())
+}
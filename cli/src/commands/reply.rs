@@ -1,32 +1,25 @@
 use anyhow::Result;
 
+use crate::commands::comment::comment_response;
 use crate::github::client::GitHubClient;
-use crate::github::types::CommentResponse;
+use crate::github::types::{CommentResponse, PrRef};
 
 pub async fn run(url: &str, comment_id: u64, body: &str) -> Result<()> {
-    let client = GitHubClient::new()?;
     let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
 
-    match client.reply_to_comment(&pr_ref, comment_id, body).await {
-        Ok(comment) => {
-            let response = CommentResponse {
-                success: true,
-                comment_id: Some(comment.id),
-                html_url: Some(comment.html_url),
-                error: None,
-            };
-            println!("{}", serde_json::to_string(&response)?);
-        }
-        Err(e) => {
-            let response = CommentResponse {
-                success: false,
-                comment_id: None,
-                html_url: None,
-                error: Some(e.to_string()),
-            };
-            println!("{}", serde_json::to_string(&response)?);
-        }
-    }
+    let response = reply(&client, &pr_ref, comment_id, body).await;
+    println!("{}", serde_json::to_string(&response)?);
 
     Ok(())
 }
+
+pub(crate) async fn reply(
+    client: &GitHubClient,
+    pr_ref: &PrRef,
+    comment_id: u64,
+    body: &str,
+) -> CommentResponse {
+    let result = client.reply_to_comment(pr_ref, comment_id, body).await;
+    comment_response(result.map(|c| (c.id, Some(c.html_url))))
+}
@@ -0,0 +1,112 @@
+use anyhow::{Result, anyhow};
+
+use crate::github::client::GitHubClient;
+use crate::github::types::{CommentResponse, ReviewResponse};
+
+/// Start a pending review, or resume the viewer's existing one
+pub async fn run_start(url: &str) -> Result<()> {
+    let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
+
+    let result = match client.get_pending_review(&pr_ref).await {
+        Ok(Some(review)) => Ok(review),
+        Ok(None) => match client.get_pr(&pr_ref).await {
+            Ok(pr) => client.start_pending_review(&pr_ref, &pr.head_sha).await,
+            Err(e) => Err(e),
+        },
+        Err(e) => Err(e),
+    };
+
+    let response = match result {
+        Ok(review) => ReviewResponse {
+            success: true,
+            review_id: Some(review.id),
+            error: None,
+        },
+        Err(e) => ReviewResponse {
+            success: false,
+            review_id: None,
+            error: Some(e.to_string()),
+        },
+    };
+    println!("{}", serde_json::to_string(&response)?);
+
+    Ok(())
+}
+
+/// Queue a comment on the viewer's pending review
+pub async fn run_add_comment(
+    url: &str,
+    path: &str,
+    line: u32,
+    side: &str,
+    body: &str,
+    start_line: Option<u32>,
+    start_side: Option<&str>,
+) -> Result<()> {
+    let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
+
+    let result = match client.get_pending_review(&pr_ref).await {
+        Ok(Some(review)) => {
+            client
+                .add_pending_review_comment(&review, path, line, side, body, start_line, start_side)
+                .await
+        }
+        Ok(None) => Err(no_pending_review_error()),
+        Err(e) => Err(e),
+    };
+
+    let response = match result {
+        Ok(comment) => CommentResponse {
+            success: true,
+            comment_id: Some(comment.id),
+            html_url: Some(comment.html_url),
+            error: None,
+        },
+        Err(e) => CommentResponse {
+            success: false,
+            comment_id: None,
+            html_url: None,
+            error: Some(e.to_string()),
+        },
+    };
+    println!("{}", serde_json::to_string(&response)?);
+
+    Ok(())
+}
+
+/// Throw away the viewer's pending review and every comment queued on it
+pub async fn run_discard(url: &str) -> Result<()> {
+    let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
+
+    let result = match client.get_pending_review(&pr_ref).await {
+        Ok(Some(review)) => client
+            .discard_pending_review(&pr_ref, review.id)
+            .await
+            .map(|()| review.id),
+        Ok(None) => Err(no_pending_review_error()),
+        Err(e) => Err(e),
+    };
+
+    let response = match result {
+        Ok(review_id) => ReviewResponse {
+            success: true,
+            review_id: Some(review_id),
+            error: None,
+        },
+        Err(e) => ReviewResponse {
+            success: false,
+            review_id: None,
+            error: Some(e.to_string()),
+        },
+    };
+    println!("{}", serde_json::to_string(&response)?);
+
+    Ok(())
+}
+
+fn no_pending_review_error() -> anyhow::Error {
+    anyhow!("No pending review found. Run `neo-reviewer review start` first.")
+}
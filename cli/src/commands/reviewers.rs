@@ -0,0 +1,215 @@
+use anyhow::{Result, anyhow};
+
+use crate::github::client::GitHubClient;
+use crate::github::types::{PrRef, Reviewer, ReviewerKind, ReviewersResponse, SubmittedReview};
+
+/// What to do with the given reviewers before listing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewersAction {
+    List,
+    Add,
+    Remove,
+    /// Re-request review; defaults to everyone who has completed a review
+    ReRequest,
+}
+
+pub async fn run(
+    url: &str,
+    action: ReviewersAction,
+    users: &[String],
+    teams: &[String],
+) -> Result<()> {
+    let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
+
+    let teams: Vec<String> = teams.iter().map(|t| team_slug(t).to_string()).collect();
+
+    let response = match apply(&client, &pr_ref, action, users, &teams).await {
+        Ok((pending, completed)) => ReviewersResponse {
+            success: true,
+            pending,
+            completed,
+            error: None,
+        },
+        Err(e) => ReviewersResponse {
+            success: false,
+            pending: Vec::new(),
+            completed: Vec::new(),
+            error: Some(e.to_string()),
+        },
+    };
+
+    println!("{}", serde_json::to_string(&response)?);
+
+    Ok(())
+}
+
+async fn apply(
+    client: &GitHubClient,
+    pr_ref: &PrRef,
+    action: ReviewersAction,
+    users: &[String],
+    teams: &[String],
+) -> Result<(Vec<Reviewer>, Vec<Reviewer>)> {
+    match action {
+        ReviewersAction::List => {}
+        ReviewersAction::Add => client.request_reviewers(pr_ref, users, teams).await?,
+        ReviewersAction::Remove => {
+            client
+                .remove_requested_reviewers(pr_ref, users, teams)
+                .await?
+        }
+        ReviewersAction::ReRequest => {
+            let users = if users.is_empty() && teams.is_empty() {
+                let (pending, completed) = list(client, pr_ref).await?;
+                let users = rerequest_candidates(&pending, &completed);
+                if users.is_empty() {
+                    return Err(anyhow!("No completed reviews to re-request"));
+                }
+                users
+            } else {
+                users.to_vec()
+            };
+            client.request_reviewers(pr_ref, &users, teams).await?;
+        }
+    }
+
+    list(client, pr_ref).await
+}
+
+async fn list(client: &GitHubClient, pr_ref: &PrRef) -> Result<(Vec<Reviewer>, Vec<Reviewer>)> {
+    let (pending, reviews) = tokio::try_join!(
+        client.get_requested_reviewers(pr_ref),
+        client.get_reviews(pr_ref)
+    )?;
+    let completed = completed_reviewers(&reviews, &pending);
+
+    Ok((pending, completed))
+}
+
+/// Reduce submitted reviews to one entry per author, in order of first
+/// review. An approval or change request is sticky: a later plain comment
+/// doesn't replace it, matching how GitHub reports review status. Authors
+/// whose review is requested again are left to the pending list.
+fn completed_reviewers(reviews: &[SubmittedReview], pending: &[Reviewer]) -> Vec<Reviewer> {
+    let mut completed: Vec<Reviewer> = Vec::new();
+
+    for review in reviews {
+        if review.author.is_empty() {
+            continue;
+        }
+
+        let reviewer = Reviewer {
+            name: review.author.clone(),
+            kind: ReviewerKind::User,
+            state: Some(review.state.clone()),
+            submitted_at: review.submitted_at.clone(),
+        };
+
+        match completed.iter_mut().find(|r| r.name == review.author) {
+            Some(existing) => {
+                let keep_existing =
+                    review.state == "COMMENTED" && existing.state.as_deref() != Some("COMMENTED");
+                if !keep_existing {
+                    *existing = reviewer;
+                }
+            }
+            None => completed.push(reviewer),
+        }
+    }
+
+    completed.retain(|r| {
+        !pending
+            .iter()
+            .any(|p| p.kind == ReviewerKind::User && p.name == r.name)
+    });
+    completed
+}
+
+fn rerequest_candidates(pending: &[Reviewer], completed: &[Reviewer]) -> Vec<String> {
+    completed
+        .iter()
+        .filter(|r| !pending.iter().any(|p| p.name == r.name))
+        .map(|r| r.name.clone())
+        .collect()
+}
+
+/// Accept `org/slug` as well as a bare team slug
+fn team_slug(team: &str) -> &str {
+    team.rsplit('/').next().unwrap_or(team)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn review(author: &str, state: &str, submitted_at: &str) -> SubmittedReview {
+        SubmittedReview {
+            id: 1,
+            author: author.to_string(),
+            state: state.to_string(),
+            submitted_at: Some(submitted_at.to_string()),
+            body: String::new(),
+            html_url: String::new(),
+            commit_id: None,
+        }
+    }
+
+    fn pending_user(name: &str) -> Reviewer {
+        Reviewer {
+            name: name.to_string(),
+            kind: ReviewerKind::User,
+            state: None,
+            submitted_at: None,
+        }
+    }
+
+    #[test]
+    fn keeps_latest_decisive_state_per_author() {
+        let reviews = vec![
+            review("alice", "CHANGES_REQUESTED", "2026-01-01T00:00:00Z"),
+            review("bob", "COMMENTED", "2026-01-02T00:00:00Z"),
+            review("alice", "COMMENTED", "2026-01-03T00:00:00Z"),
+            review("bob", "APPROVED", "2026-01-04T00:00:00Z"),
+        ];
+
+        let completed = completed_reviewers(&reviews, &[]);
+
+        assert_eq!(completed.len(), 2);
+        assert_eq!(completed[0].name, "alice");
+        assert_eq!(completed[0].state.as_deref(), Some("CHANGES_REQUESTED"));
+        assert_eq!(completed[1].name, "bob");
+        assert_eq!(completed[1].state.as_deref(), Some("APPROVED"));
+    }
+
+    #[test]
+    fn re_requested_reviewers_are_only_pending() {
+        let reviews = vec![review("alice", "APPROVED", "2026-01-01T00:00:00Z")];
+
+        let completed = completed_reviewers(&reviews, &[pending_user("alice")]);
+
+        assert!(completed.is_empty());
+    }
+
+    #[test]
+    fn rerequest_defaults_to_completed_reviewers() {
+        let completed = completed_reviewers(
+            &[
+                review("alice", "APPROVED", "2026-01-01T00:00:00Z"),
+                review("bob", "COMMENTED", "2026-01-01T00:00:00Z"),
+            ],
+            &[],
+        );
+
+        assert_eq!(
+            rerequest_candidates(&[pending_user("bob")], &completed),
+            vec!["alice".to_string()]
+        );
+    }
+
+    #[test]
+    fn strips_org_from_team_slug() {
+        assert_eq!(team_slug("my-org/platform"), "platform");
+        assert_eq!(team_slug("platform"), "platform");
+    }
+}
@@ -0,0 +1,340 @@
+use anyhow::Result;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use crate::commands::comment::{
+    LineComment, add_line_comment, add_pr_level_comment, delete_comment, edit_comment,
+};
+use crate::commands::diff::{LocalDiffCliOpts, local_diff};
+use crate::commands::fetch::{DiffCache, FetchOpts, fetch};
+use crate::commands::reply::reply;
+use crate::commands::submit::{parse_review_comments, submit, validate_review};
+use crate::github::client::GitHubClient;
+use crate::github::types::{PrRef, PullRequest, ReviewEvent};
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+/// Answer JSON-RPC 2.0 requests, one per line on stdin, until stdin closes.
+///
+/// Methods mirror the commands of the same name and return their JSON
+/// output: `fetch`, `diff`, `comment`, `reply`, `edit`, `delete` and
+/// `submit`. Params are objects named after the command's flags, e.g.
+/// `{"url": ..., "comment_id": 42, "body": "..."}` for `reply`.
+///
+/// Line comments are anchored to the PR head of the latest `fetch`, which is
+/// what the editor shows, even if the PR has been pushed to since; `fetch`
+/// again to review (and comment on) the new head. Before any `fetch` the
+/// current head is read once and then kept the same way.
+pub async fn run() -> Result<()> {
+    let mut session = Session::default();
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = session.handle(&line).await {
+            println!("{}", serde_json::to_string(&response)?);
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct Request {
+    /// Absent for notifications, which get no response
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::new(SERVER_ERROR, e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for RpcError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::new(SERVER_ERROR, e.to_string())
+    }
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+fn params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+#[derive(Deserialize)]
+struct FetchParams {
+    url: String,
+    #[serde(flatten)]
+    opts: FetchOpts,
+}
+
+#[derive(Deserialize)]
+struct CommentParams {
+    url: String,
+    path: Option<String>,
+    line: Option<u32>,
+    #[serde(default = "default_side")]
+    side: String,
+    body: String,
+    start_line: Option<u32>,
+    start_side: Option<String>,
+    commit: Option<String>,
+}
+
+fn default_side() -> String {
+    "RIGHT".to_string()
+}
+
+#[derive(Deserialize)]
+struct CommentBodyParams {
+    url: String,
+    comment_id: u64,
+    body: String,
+}
+
+#[derive(Deserialize)]
+struct DeleteParams {
+    url: String,
+    comment_id: u64,
+}
+
+#[derive(Deserialize)]
+struct SubmitParams {
+    url: String,
+    event: ReviewEvent,
+    body: Option<String>,
+    comments: Option<Value>,
+}
+
+/// State kept between requests so each one skips auth, client setup and
+/// re-reading what an earlier request already fetched
+#[derive(Default)]
+struct Session {
+    /// Authenticated clients by host
+    clients: HashMap<String, Arc<GitHubClient>>,
+    /// PR metadata by PR URL, replaced by every `fetch` and never refreshed
+    /// otherwise, so comments keep anchoring to the head being reviewed
+    prs: HashMap<String, PullRequest>,
+    diffs: DiffCache,
+}
+
+impl Session {
+    async fn handle(&mut self, line: &str) -> Option<Value> {
+        let request: Request = match serde_json::from_str::<Value>(line) {
+            Ok(value) => match serde_json::from_value(value) {
+                Ok(request) => request,
+                Err(e) => {
+                    return Some(error_response(
+                        Value::Null,
+                        RpcError::new(INVALID_REQUEST, e.to_string()),
+                    ));
+                }
+            },
+            Err(e) => {
+                return Some(error_response(
+                    Value::Null,
+                    RpcError::new(PARSE_ERROR, e.to_string()),
+                ));
+            }
+        };
+
+        let result = self.dispatch(&request.method, request.params).await;
+        let id = request.id?;
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => error_response(id, error),
+        })
+    }
+
+    async fn dispatch(&mut self, method: &str, raw_params: Value) -> Result<Value, RpcError> {
+        match method {
+            "fetch" => {
+                let p: FetchParams = params(raw_params)?;
+                let (client, pr_ref) = self.client_for(&p.url)?;
+                let response = fetch(&client, &pr_ref, p.opts, &mut self.diffs).await?;
+                self.prs.insert(pr_ref.url(), response.pr.clone());
+                Ok(serde_json::to_value(response)?)
+            }
+            "diff" => {
+                let opts: LocalDiffCliOpts = params(raw_params)?;
+                Ok(serde_json::to_value(local_diff(opts)?)?)
+            }
+            "comment" => {
+                let p: CommentParams = params(raw_params)?;
+                if p.path.is_some() != p.line.is_some() {
+                    return Err(RpcError::new(
+                        INVALID_PARAMS,
+                        "path and line must be given together",
+                    ));
+                }
+
+                let (client, pr_ref) = self.client_for(&p.url)?;
+                let response = match (p.path, p.line) {
+                    (Some(path), Some(line)) => {
+                        // Anchor to the head the editor last fetched.
+                        let head_sha = self.pr(&client, &pr_ref).await?.head_sha;
+                        let comment = LineComment {
+                            path,
+                            line,
+                            side: p.side,
+                            body: p.body,
+                            start_line: p.start_line,
+                            start_side: p.start_side,
+                            commit: p.commit,
+                        };
+                        add_line_comment(&client, &pr_ref, &head_sha, &comment).await?
+                    }
+                    _ => add_pr_level_comment(&client, &pr_ref, &p.body).await,
+                };
+                Ok(serde_json::to_value(response)?)
+            }
+            "reply" => {
+                let p: CommentBodyParams = params(raw_params)?;
+                let (client, pr_ref) = self.client_for(&p.url)?;
+                let response = reply(&client, &pr_ref, p.comment_id, &p.body).await;
+                Ok(serde_json::to_value(response)?)
+            }
+            "edit" => {
+                let p: CommentBodyParams = params(raw_params)?;
+                let (client, pr_ref) = self.client_for(&p.url)?;
+                let response = edit_comment(&client, &pr_ref, p.comment_id, &p.body).await;
+                Ok(serde_json::to_value(response)?)
+            }
+            "delete" => {
+                let p: DeleteParams = params(raw_params)?;
+                let (client, pr_ref) = self.client_for(&p.url)?;
+                let response = delete_comment(&client, &pr_ref, p.comment_id).await;
+                Ok(serde_json::to_value(response)?)
+            }
+            "submit" => {
+                let p: SubmitParams = params(raw_params)?;
+                let comments = match &p.comments {
+                    Some(comments) => parse_review_comments(&comments.to_string())
+                        .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))?,
+                    None => Vec::new(),
+                };
+                validate_review(p.event, p.body.as_deref(), &comments)
+                    .map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))?;
+
+                let (client, pr_ref) = self.client_for(&p.url)?;
+                Ok(submit(&client, &pr_ref, p.event, p.body.as_deref(), &comments).await?)
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Unknown method '{}'", method),
+            )),
+        }
+    }
+
+    fn client_for(&mut self, url: &str) -> Result<(Arc<GitHubClient>, PrRef)> {
+        let pr_ref = GitHubClient::parse_pr_url(url)?;
+        let client = match self.clients.get(&pr_ref.host) {
+            Some(client) => client.clone(),
+            None => {
+                let client = Arc::new(GitHubClient::new(&pr_ref.host)?);
+                self.clients.insert(pr_ref.host.clone(), client.clone());
+                client
+            }
+        };
+        Ok((client, pr_ref))
+    }
+
+    /// The PR as last fetched in this session, read once if it never was
+    async fn pr(&mut self, client: &GitHubClient, pr_ref: &PrRef) -> Result<PullRequest> {
+        if let Some(pr) = self.prs.get(&pr_ref.url()) {
+            return Ok(pr.clone());
+        }
+        let pr = client.get_pr(pr_ref).await?;
+        self.prs.insert(pr_ref.url(), pr.clone());
+        Ok(pr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn handle_reports_parse_errors_and_unknown_methods() {
+        let mut session = Session::default();
+
+        let response = session.handle("{not json").await.unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+        assert_eq!(response["id"], Value::Null);
+
+        let response = session
+            .handle(r#"{"jsonrpc":"2.0","id":7,"method":"rebase","params":{}}"#)
+            .await
+            .unwrap();
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn handle_rejects_bad_params_and_skips_notification_responses() {
+        let mut session = Session::default();
+
+        let response = session
+            .handle(r#"{"jsonrpc":"2.0","id":1,"method":"reply","params":{"url":"x"}}"#)
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+        let response = session
+            .handle(r#"{"jsonrpc":"2.0","method":"rebase"}"#)
+            .await;
+        assert!(response.is_none());
+    }
+
+    #[tokio::test]
+    async fn comment_requires_path_and_line_together() {
+        let mut session = Session::default();
+
+        let response = session
+            .handle(
+                r#"{"jsonrpc":"2.0","id":2,"method":"comment","params":{"url":"https://github.com/owner/repo/pull/1","path":"a.rs","body":"x"}}"#,
+            )
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+}
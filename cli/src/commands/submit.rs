@@ -1,20 +1,142 @@
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 
 use crate::github::client::GitHubClient;
+use crate::github::types::{DraftReviewComment, PrRef, ReviewEvent};
+
+pub async fn run(
+    url: &str,
+    event: ReviewEvent,
+    body: Option<&str>,
+    comments_json: Option<&str>,
+) -> Result<()> {
+    let comments = match comments_json {
+        Some(json) => parse_review_comments(json)?,
+        None => Vec::new(),
+    };
+    validate_review(event, body, &comments)?;
 
-pub async fn run(url: &str, event: &str, body: Option<&str>) -> Result<()> {
-    let client = GitHubClient::new()?;
     let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
 
-    client.submit_review(&pr_ref, event, body).await?;
+    let response = submit(&client, &pr_ref, event, body, &comments).await?;
+    println!("{}", response);
 
-    println!(
-        "{}",
-        serde_json::json!({
-            "success": true,
-            "event": event,
-        })
-    );
+    Ok(())
+}
 
+/// Submit a review, publishing any comments queued with `review add-comment`
+/// as part of it
+pub(crate) async fn submit(
+    client: &GitHubClient,
+    pr_ref: &PrRef,
+    event: ReviewEvent,
+    body: Option<&str>,
+    comments: &[DraftReviewComment],
+) -> Result<serde_json::Value> {
+    let review_id = match client.get_pending_review(pr_ref).await? {
+        Some(_) if !comments.is_empty() => {
+            return Err(anyhow!(
+                "A pending review already exists. Submit or discard it before passing --comments."
+            ));
+        }
+        Some(review) => {
+            client
+                .submit_pending_review(pr_ref, review.id, event, body)
+                .await?;
+            Some(review.id)
+        }
+        None => {
+            client.submit_review(pr_ref, event, body, comments).await?;
+            None
+        }
+    };
+
+    Ok(serde_json::json!({
+        "success": true,
+        "event": event,
+        "review_id": review_id,
+        "comments": comments.len(),
+    }))
+}
+
+pub(crate) fn validate_review(
+    event: ReviewEvent,
+    body: Option<&str>,
+    comments: &[DraftReviewComment],
+) -> Result<()> {
+    let has_body = body.is_some_and(|b| !b.trim().is_empty());
+    if event == ReviewEvent::Comment && !has_body && comments.is_empty() {
+        return Err(anyhow!(
+            "A COMMENT review needs a body or at least one inline comment"
+        ));
+    }
     Ok(())
 }
+
+pub(crate) fn parse_review_comments(json: &str) -> Result<Vec<DraftReviewComment>> {
+    let mut comments: Vec<DraftReviewComment> =
+        serde_json::from_str(json).map_err(|e| anyhow!("Invalid --comments JSON: {}", e))?;
+
+    for comment in &mut comments {
+        comment.side = comment.side.to_uppercase();
+        comment.start_side = comment.start_side.as_ref().map(|s| s.to_uppercase());
+
+        if let Some(start_line) = comment.start_line
+            && start_line > comment.line
+        {
+            return Err(anyhow!(
+                "Invalid comment on {}: start_line {} is after line {}",
+                comment.path,
+                start_line,
+                comment.line
+            ));
+        }
+    }
+
+    Ok(comments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_review_comments_defaults_side_to_right() {
+        let comments =
+            parse_review_comments(r#"[{"path":"src/lib.rs","line":3,"body":"nit"}]"#).unwrap();
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].side, "RIGHT");
+        assert_eq!(comments[0].start_line, None);
+    }
+
+    #[test]
+    fn parse_review_comments_normalizes_sides() {
+        let comments = parse_review_comments(
+            r#"[{"path":"a.rs","line":5,"side":"left","start_line":2,"start_side":"left","body":"x"}]"#,
+        )
+        .unwrap();
+        assert_eq!(comments[0].side, "LEFT");
+        assert_eq!(comments[0].start_side.as_deref(), Some("LEFT"));
+    }
+
+    #[test]
+    fn parse_review_comments_rejects_inverted_range() {
+        let result =
+            parse_review_comments(r#"[{"path":"a.rs","line":2,"start_line":5,"body":"x"}]"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_review_comments_rejects_missing_fields() {
+        assert!(parse_review_comments(r#"[{"path":"a.rs","body":"x"}]"#).is_err());
+        assert!(parse_review_comments("not json").is_err());
+    }
+
+    #[test]
+    fn validate_review_requires_content_for_comment_event() {
+        assert!(validate_review(ReviewEvent::Comment, None, &[]).is_err());
+        assert!(validate_review(ReviewEvent::Comment, Some("  "), &[]).is_err());
+        assert!(validate_review(ReviewEvent::Comment, Some("LGTM"), &[]).is_ok());
+        assert!(validate_review(ReviewEvent::Approve, None, &[]).is_ok());
+    }
+}
@@ -0,0 +1,235 @@
+use anyhow::{Result, anyhow};
+use std::ops::RangeInclusive;
+
+use crate::commands::diff::get_pr_file_hunks;
+use crate::commands::fetch::ensure_pr_commits_available;
+use crate::github::client::GitHubClient;
+use crate::github::types::CommentResponse;
+
+pub async fn run(
+    url: &str,
+    path: &str,
+    start_line: Option<u32>,
+    line: u32,
+    replacement: &str,
+    message: Option<&str>,
+) -> Result<()> {
+    let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
+
+    let pr = client.get_pr(&pr_ref).await?;
+    ensure_pr_commits_available(&pr_ref, &pr)?;
+
+    let start_line = start_line.unwrap_or(line);
+    let result = get_pr_file_hunks(&pr.base_sha, &pr.head_sha, path).and_then(|hunks| {
+        if hunks.is_empty() {
+            return Err(anyhow!("{} has no changes in this PR", path));
+        }
+        validate_suggestion_range(&hunks, path, start_line, line)
+    });
+
+    let result = match result {
+        Ok(()) => {
+            let body = build_suggestion_body(message, replacement);
+            let multi_line_start = (start_line < line).then_some(start_line);
+            client
+                .add_review_comment(
+                    &pr_ref,
+                    &pr.head_sha,
+                    path,
+                    line,
+                    "RIGHT",
+                    &body,
+                    multi_line_start,
+                    multi_line_start.map(|_| "RIGHT"),
+                )
+                .await
+        }
+        Err(e) => Err(e),
+    };
+
+    let response = match result {
+        Ok(comment) => CommentResponse {
+            success: true,
+            comment_id: Some(comment.id),
+            html_url: Some(comment.html_url),
+            error: None,
+        },
+        Err(e) => CommentResponse {
+            success: false,
+            comment_id: None,
+            html_url: None,
+            error: Some(e.to_string()),
+        },
+    };
+    println!("{}", serde_json::to_string(&response)?);
+
+    Ok(())
+}
+
+/// Build a comment body containing a ```suggestion block, widening the fence
+/// when the replacement itself contains backtick fences.
+fn build_suggestion_body(message: Option<&str>, replacement: &str) -> String {
+    let replacement = replacement.strip_suffix('\n').unwrap_or(replacement);
+    let longest_run = replacement
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+
+    let mut body = String::new();
+    if let Some(message) = message.map(str::trim).filter(|m| !m.is_empty()) {
+        body.push_str(message);
+        body.push_str("\n\n");
+    }
+    body.push_str(&format!("{fence}suggestion\n"));
+    if !replacement.is_empty() {
+        body.push_str(replacement);
+        body.push('\n');
+    }
+    body.push_str(&fence);
+    body
+}
+
+/// Check that a new-file line range can carry a suggestion: both ends must
+/// lie in the same hunk of the PR diff, since GitHub anchors a multi-line
+/// comment to a single hunk. Context lines and several change blocks within
+/// the hunk are fine.
+fn validate_suggestion_range(
+    hunks: &[RangeInclusive<u32>],
+    path: &str,
+    start_line: u32,
+    end_line: u32,
+) -> Result<()> {
+    if start_line == 0 || start_line > end_line {
+        return Err(anyhow!(
+            "Invalid line range {}-{}: start line must be between 1 and the end line",
+            start_line,
+            end_line
+        ));
+    }
+
+    let hunk_of = |line: u32| hunks.iter().position(|h| h.contains(&line));
+    match (hunk_of(start_line), hunk_of(end_line)) {
+        (Some(start), Some(end)) if start == end => Ok(()),
+        (Some(start), Some(end)) => Err(anyhow!(
+            "Lines {}-{} of {} span {} hunks (lines {}-{} to {}-{}); split the suggestion per hunk",
+            start_line,
+            end_line,
+            path,
+            end - start + 1,
+            hunks[start].start(),
+            hunks[start].end(),
+            hunks[end].start(),
+            hunks[end].end()
+        )),
+        (start, _) => Err(anyhow!(
+            "Line {} of {} is outside the diff; suggestions can only be made on lines shown in the PR diff",
+            if start.is_none() {
+                start_line
+            } else {
+                end_line
+            },
+            path
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::diff::new_side_hunks;
+
+    fn hunks() -> Vec<RangeInclusive<u32>> {
+        new_side_hunks(
+            r#"@@ -1,12 +1,13 @@
+ line1
+-old2
+-old3
++new2
++new3
++new4
+ line4
+ line5
+-gone6
+ line7
+ line8
+ line9
++add10
++add11
+ line10
+@@ -30,4 +31,5 @@
+ line30
+ line31
++add33
+ line32
+ line33"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn accepts_range_within_single_hunk() {
+        assert!(validate_suggestion_range(&hunks(), "a.rs", 2, 4).is_ok());
+        assert!(validate_suggestion_range(&hunks(), "a.rs", 11, 11).is_ok());
+        assert!(validate_suggestion_range(&hunks(), "a.rs", 32, 34).is_ok());
+    }
+
+    #[test]
+    fn accepts_context_lines_and_several_blocks_in_one_hunk() {
+        assert!(validate_suggestion_range(&hunks(), "a.rs", 1, 1).is_ok());
+        assert!(validate_suggestion_range(&hunks(), "a.rs", 2, 12).is_ok());
+        assert!(validate_suggestion_range(&hunks(), "a.rs", 6, 8).is_ok());
+    }
+
+    #[test]
+    fn rejects_range_outside_diff() {
+        let err = validate_suggestion_range(&hunks(), "a.rs", 20, 20).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Line 20 of a.rs is outside the diff")
+        );
+    }
+
+    #[test]
+    fn rejects_range_extending_past_hunk() {
+        let err = validate_suggestion_range(&hunks(), "a.rs", 12, 14).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Line 14 of a.rs is outside the diff")
+        );
+    }
+
+    #[test]
+    fn rejects_range_spanning_two_hunks() {
+        let err = validate_suggestion_range(&hunks(), "a.rs", 10, 32).unwrap_err();
+        assert!(err.to_string().contains("span 2 hunks"));
+    }
+
+    #[test]
+    fn rejects_inverted_range() {
+        assert!(validate_suggestion_range(&hunks(), "a.rs", 4, 2).is_err());
+    }
+
+    #[test]
+    fn build_suggestion_body_with_message() {
+        let body = build_suggestion_body(Some("Use the helper"), "let x = helper();\n");
+        assert_eq!(
+            body,
+            "Use the helper\n\n```suggestion\nlet x = helper();\n```"
+        );
+    }
+
+    #[test]
+    fn build_suggestion_body_for_deletion() {
+        assert_eq!(build_suggestion_body(None, ""), "```suggestion\n```");
+    }
+
+    #[test]
+    fn build_suggestion_body_widens_fence_for_nested_backticks() {
+        let body = build_suggestion_body(None, "```rust\nfn main() {}\n```");
+        assert!(body.starts_with("````suggestion\n"));
+        assert!(body.ends_with("\n````"));
+    }
+}
@@ -0,0 +1,45 @@
+use anyhow::Result;
+
+use crate::github::client::GitHubClient;
+use crate::github::types::{ThreadResponse, ThreadsResponse};
+
+pub async fn run(url: &str) -> Result<()> {
+    let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
+
+    let threads = client.get_review_threads(&pr_ref).await?;
+
+    let response = ThreadsResponse { threads };
+
+    println!("{}", serde_json::to_string(&response)?);
+
+    Ok(())
+}
+
+pub async fn run_set_resolved(url: &str, thread_id: &str, resolved: bool) -> Result<()> {
+    let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
+
+    match client.set_thread_resolved(thread_id, resolved).await {
+        Ok(is_resolved) => {
+            let response = ThreadResponse {
+                success: true,
+                thread_id: Some(thread_id.to_string()),
+                is_resolved: Some(is_resolved),
+                error: None,
+            };
+            println!("{}", serde_json::to_string(&response)?);
+        }
+        Err(e) => {
+            let response = ThreadResponse {
+                success: false,
+                thread_id: None,
+                is_resolved: None,
+                error: Some(e.to_string()),
+            };
+            println!("{}", serde_json::to_string(&response)?);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use anyhow::Result;
+
+use crate::github::client::GitHubClient;
+use crate::github::types::{ViewedResponse, ViewedState};
+
+pub async fn run_set_viewed(url: &str, path: &str, viewed: bool) -> Result<()> {
+    let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
+
+    match client.set_file_viewed(&pr_ref, path, viewed).await {
+        Ok(()) => {
+            let response = ViewedResponse {
+                success: true,
+                path: path.to_string(),
+                viewed_state: Some(if viewed {
+                    ViewedState::Viewed
+                } else {
+                    ViewedState::Unviewed
+                }),
+                error: None,
+            };
+            println!("{}", serde_json::to_string(&response)?);
+        }
+        Err(e) => {
+            let response = ViewedResponse {
+                success: false,
+                path: path.to_string(),
+                viewed_state: None,
+                error: Some(e.to_string()),
+            };
+            println!("{}", serde_json::to_string(&response)?);
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,360 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use crate::commands::fetch::optional;
+use crate::github::client::GitHubClient;
+use crate::github::types::{CheckState, ChecksSummary, PrRef, ReviewComment, WatchEvent};
+
+/// Poll a PR and print one NDJSON event per change until interrupted.
+///
+/// Every poll is a handful of conditional requests, which GitHub answers
+/// with 304 (not counted against the rate limit) while nothing changed.
+/// Comments are listed with `since`, so a change only downloads what was
+/// touched; the full list is re-read to spot deletions only when something
+/// changed or the PR's comment count disagrees.
+pub async fn run(url: &str, interval_secs: u64) -> Result<()> {
+    let pr_ref = GitHubClient::parse_pr_url(url)?;
+    let client = GitHubClient::new(&pr_ref.host)?;
+
+    let mut watcher = Watcher::start(&client, &pr_ref).await?;
+    emit(&WatchEvent::Started {
+        head_sha: watcher.head_sha.clone(),
+        checks: watcher.checks.map(|key| key.0),
+    })?;
+
+    let interval = Duration::from_secs(interval_secs.max(1));
+    loop {
+        tokio::time::sleep(interval).await;
+
+        match watcher.poll(&client, &pr_ref).await {
+            Ok(events) => {
+                for event in &events {
+                    emit(event)?;
+                }
+            }
+            Err(e) => emit(&WatchEvent::Error {
+                message: e.to_string(),
+            })?,
+        }
+    }
+}
+
+fn emit(event: &WatchEvent) -> Result<()> {
+    println!("{}", serde_json::to_string(event)?);
+    Ok(())
+}
+
+/// Check state plus passed/failed/pending counts
+type ChecksKey = (CheckState, u32, u32, u32);
+
+struct Watcher {
+    head_sha: String,
+    comments: HashMap<u64, ReviewComment>,
+    reviews: HashSet<u64>,
+    /// `None` until checks could be read
+    checks: Option<ChecksKey>,
+    /// Whether the last checks read failed, so a lasting failure warns once
+    checks_failing: bool,
+}
+
+impl Watcher {
+    async fn start(client: &GitHubClient, pr_ref: &PrRef) -> Result<Self> {
+        let (pr, comments, reviews) = tokio::try_join!(
+            client.get_pr(pr_ref),
+            client.get_review_comments_since(pr_ref, None),
+            client.get_reviews(pr_ref)
+        )?;
+        let mut checks_failing = false;
+        let checks = optional_once(
+            client.get_checks(pr_ref, &pr.head_sha).await,
+            &mut checks_failing,
+            "checks",
+        );
+
+        Ok(Self {
+            head_sha: pr.head_sha,
+            comments: comments.into_iter().map(|c| (c.id, c)).collect(),
+            reviews: reviews.into_iter().map(|r| r.id).collect(),
+            checks: checks.map(|checks| checks_key(&ChecksSummary::from_checks(checks))),
+            checks_failing,
+        })
+    }
+
+    async fn poll(&mut self, client: &GitHubClient, pr_ref: &PrRef) -> Result<Vec<WatchEvent>> {
+        let since = latest_update(self.comments.values());
+        let (pr, changed, reviews) = tokio::try_join!(
+            client.get_pr(pr_ref),
+            client.get_review_comments_since(pr_ref, since.as_deref()),
+            client.get_reviews(pr_ref)
+        )?;
+        let mut events = Vec::new();
+
+        if pr.head_sha != self.head_sha {
+            events.push(WatchEvent::HeadChanged {
+                from: std::mem::replace(&mut self.head_sha, pr.head_sha.clone()),
+                to: pr.head_sha.clone(),
+            });
+        }
+
+        let changes = merge_comments(&mut self.comments, changed);
+        let relist = needs_full_listing(&changes, self.comments.len(), pr.review_comment_count);
+        events.extend(changes);
+        if relist {
+            let all = client.get_review_comments_since(pr_ref, None).await?;
+            events.extend(remove_deleted(&mut self.comments, &all));
+        }
+
+        for review in reviews {
+            if self.reviews.insert(review.id) {
+                events.push(WatchEvent::ReviewSubmitted { review });
+            }
+        }
+
+        // Unreadable checks keep the last known state rather than failing the poll
+        let checks = client.get_checks(pr_ref, &self.head_sha).await;
+        if let Some(checks) = optional_once(checks, &mut self.checks_failing, "checks") {
+            let summary = ChecksSummary::from_checks(checks);
+            let key = checks_key(&summary);
+            if self.checks != Some(key) {
+                self.checks = Some(key);
+                events.push(WatchEvent::ChecksChanged {
+                    head_sha: self.head_sha.clone(),
+                    state: summary.state,
+                    passed: summary.passed,
+                    failed: summary.failed,
+                    pending: summary.pending,
+                });
+            }
+        }
+
+        Ok(events)
+    }
+}
+
+/// Like `optional`, but a failure that lasts across polls is only reported
+/// once; `failing` tracks whether the previous attempt failed
+fn optional_once<T>(result: Result<T>, failing: &mut bool, what: &str) -> Option<T> {
+    match result {
+        Ok(value) => {
+            *failing = false;
+            Some(value)
+        }
+        Err(_) if *failing => None,
+        Err(e) => {
+            *failing = true;
+            optional(Err(e), what)
+        }
+    }
+}
+
+fn checks_key(summary: &ChecksSummary) -> ChecksKey {
+    (
+        summary.state,
+        summary.passed,
+        summary.failed,
+        summary.pending,
+    )
+}
+
+/// Newest creation or edit time, used as the next `since` filter
+fn latest_update<'a>(comments: impl Iterator<Item = &'a ReviewComment>) -> Option<String> {
+    comments
+        .map(|c| c.updated_at.as_ref().unwrap_or(&c.created_at))
+        .max()
+        .cloned()
+}
+
+/// Record comments returned by a `since` listing. `since` is inclusive, so
+/// unchanged comments come back and are skipped.
+fn merge_comments(
+    known: &mut HashMap<u64, ReviewComment>,
+    changed: Vec<ReviewComment>,
+) -> Vec<WatchEvent> {
+    let mut events = Vec::new();
+
+    for comment in changed {
+        match known.get(&comment.id) {
+            None => events.push(WatchEvent::CommentAdded {
+                comment: comment.clone(),
+            }),
+            Some(old) if old.body != comment.body || old.line != comment.line => {
+                events.push(WatchEvent::CommentEdited {
+                    comment: comment.clone(),
+                })
+            }
+            Some(_) => {}
+        }
+        known.insert(comment.id, comment);
+    }
+
+    events
+}
+
+/// Whether deletions may have happened since the last poll. A count mismatch
+/// means one did; any other change may hide one, since an add and a delete in
+/// the same interval cancel out. The full listing is a conditional request,
+/// so re-reading it while nothing changed is free.
+fn needs_full_listing(changes: &[WatchEvent], known: usize, comment_count: u32) -> bool {
+    !changes.is_empty() || known != comment_count as usize
+}
+
+/// Drop comments missing from a full listing
+fn remove_deleted(
+    known: &mut HashMap<u64, ReviewComment>,
+    all: &[ReviewComment],
+) -> Vec<WatchEvent> {
+    let current: HashSet<u64> = all.iter().map(|c| c.id).collect();
+    let mut deleted: Vec<ReviewComment> = Vec::new();
+    known.retain(|id, comment| {
+        let keep = current.contains(id);
+        if !keep {
+            deleted.push(comment.clone());
+        }
+        keep
+    });
+    deleted.sort_by_key(|c| c.id);
+
+    deleted
+        .into_iter()
+        .map(|c| WatchEvent::CommentDeleted {
+            comment_id: c.id,
+            path: c.path,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(id: u64, body: &str, updated_at: &str) -> ReviewComment {
+        ReviewComment {
+            id,
+            path: "src/lib.rs".to_string(),
+            line: Some(10),
+            side: "RIGHT".to_string(),
+            body: body.to_string(),
+            author: "reviewer".to_string(),
+            created_at: "2026-01-01T00:00:00Z".to_string(),
+            updated_at: Some(updated_at.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn event_names(events: &[WatchEvent]) -> Vec<String> {
+        events
+            .iter()
+            .map(|e| {
+                serde_json::to_value(e).unwrap()["event"]
+                    .as_str()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn optional_once_tracks_a_lasting_failure() {
+        let mut failing = false;
+
+        assert_eq!(
+            optional_once::<u32>(Err(anyhow::anyhow!("403")), &mut failing, "checks"),
+            None
+        );
+        assert!(failing);
+        assert_eq!(
+            optional_once::<u32>(Err(anyhow::anyhow!("403")), &mut failing, "checks"),
+            None
+        );
+        assert!(failing);
+        assert_eq!(optional_once(Ok(1), &mut failing, "checks"), Some(1));
+        assert!(!failing);
+    }
+
+    #[test]
+    fn merge_comments_reports_new_and_edited_comments_only() {
+        let mut known: HashMap<u64, ReviewComment> =
+            [(1, comment(1, "Nit", "2026-01-01T00:00:00Z"))].into();
+
+        let events = merge_comments(
+            &mut known,
+            vec![
+                comment(1, "Nit", "2026-01-01T00:00:00Z"),
+                comment(2, "Why?", "2026-01-02T00:00:00Z"),
+            ],
+        );
+        assert_eq!(event_names(&events), vec!["comment_added"]);
+
+        let events = merge_comments(
+            &mut known,
+            vec![comment(1, "Nit: rename", "2026-01-03T00:00:00Z")],
+        );
+        assert_eq!(event_names(&events), vec!["comment_edited"]);
+        assert_eq!(known[&1].body, "Nit: rename");
+    }
+
+    #[test]
+    fn remove_deleted_drops_missing_comments() {
+        let mut known: HashMap<u64, ReviewComment> = [
+            (1, comment(1, "Nit", "2026-01-01T00:00:00Z")),
+            (2, comment(2, "Why?", "2026-01-02T00:00:00Z")),
+        ]
+        .into();
+
+        let events = remove_deleted(&mut known, &[comment(2, "Why?", "2026-01-02T00:00:00Z")]);
+
+        assert_eq!(event_names(&events), vec!["comment_deleted"]);
+        assert!(!known.contains_key(&1));
+    }
+
+    #[test]
+    fn comment_added_and_another_deleted_in_one_interval() {
+        let mut known: HashMap<u64, ReviewComment> = [
+            (1, comment(1, "Nit", "2026-01-01T00:00:00Z")),
+            (2, comment(2, "Why?", "2026-01-02T00:00:00Z")),
+        ]
+        .into();
+
+        // Comment 2 was deleted and comment 3 added: the count stays at 2.
+        let changes = merge_comments(
+            &mut known,
+            vec![
+                comment(1, "Nit", "2026-01-01T00:00:00Z"),
+                comment(3, "Done", "2026-01-03T00:00:00Z"),
+            ],
+        );
+        assert_eq!(event_names(&changes), vec!["comment_added"]);
+        assert!(needs_full_listing(&changes, 2, 2));
+
+        let all = [
+            comment(1, "Nit", "2026-01-01T00:00:00Z"),
+            comment(3, "Done", "2026-01-03T00:00:00Z"),
+        ];
+        let events = remove_deleted(&mut known, &all);
+        assert_eq!(event_names(&events), vec!["comment_deleted"]);
+        let mut ids: Vec<u64> = known.keys().copied().collect();
+        ids.sort();
+        assert_eq!(ids, vec![1, 3]);
+    }
+
+    #[test]
+    fn needs_full_listing_only_when_something_changed() {
+        assert!(!needs_full_listing(&[], 2, 2));
+        assert!(needs_full_listing(&[], 2, 1));
+    }
+
+    #[test]
+    fn latest_update_prefers_edit_times() {
+        let comments = [
+            comment(1, "Nit", "2026-01-05T00:00:00Z"),
+            comment(2, "Why?", "2026-01-02T00:00:00Z"),
+        ];
+
+        assert_eq!(
+            latest_update(comments.iter()).as_deref(),
+            Some("2026-01-05T00:00:00Z")
+        );
+        assert_eq!(latest_update(std::iter::empty()), None);
+    }
+}
@@ -59,6 +59,7 @@ impl BlockBuilder {
             changed_lines: self.changed_lines,
             deletion_groups: self.deletion_groups,
             old_to_new: self.old_to_new,
+            annotations: Vec::new(),
         })
     }
 }
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::github::types::Annotation;
+
 /// A change block represents a contiguous block of changes without context lines.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChangeBlock {
@@ -17,6 +19,9 @@ pub struct ChangeBlock {
     pub deletion_groups: Vec<DeletionGroup>,
     /// Mapping from old line numbers to new anchor lines (for LEFT-side comments)
     pub old_to_new: Vec<OldToNewMap>,
+    /// CI annotations overlapping this block's lines
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<Annotation>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1,10 +1,16 @@
 use anyhow::{Result, anyhow};
 use std::process::Command;
 
-/// Get GitHub token, prioritizing `gh auth token` for SSO compatibility
+use super::client::GitHubClient;
+use super::types::DEFAULT_HOST;
+
+/// Get GitHub token for a host, prioritizing `gh auth token` for SSO compatibility
 #[allow(clippy::collapsible_if)]
-pub fn get_token() -> Result<String> {
-    if let Ok(output) = Command::new("gh").args(["auth", "token"]).output() {
+pub fn get_token(host: &str) -> Result<String> {
+    if let Ok(output) = Command::new("gh")
+        .args(["auth", "token", "--hostname", host])
+        .output()
+    {
         if output.status.success() {
             let token = String::from_utf8(output.stdout)?.trim().to_string();
             if !token.is_empty() {
@@ -13,32 +19,48 @@ pub fn get_token() -> Result<String> {
         }
     }
 
-    // Fall back to GITHUB_TOKEN environment variable
-    std::env::var("GITHUB_TOKEN").map_err(|_| {
-        anyhow!(
-            "No GitHub token found.\n\
-             Either run `gh auth login` or set the GITHUB_TOKEN environment variable."
-        )
-    })
+    // Fall back to environment variables, following gh's naming for Enterprise hosts
+    token_env_vars(host)
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|t| !t.is_empty()))
+        .ok_or_else(|| {
+            anyhow!(
+                "No GitHub token found for {}.\n\
+                 Either run `gh auth login --hostname {}` or set the {} environment variable.",
+                host,
+                host,
+                token_env_vars(host).join(" or ")
+            )
+        })
+}
+
+fn token_env_vars(host: &str) -> &'static [&'static str] {
+    if host == DEFAULT_HOST {
+        &["GITHUB_TOKEN"]
+    } else {
+        &[
+            "GH_ENTERPRISE_TOKEN",
+            "GITHUB_ENTERPRISE_TOKEN",
+            "GITHUB_TOKEN",
+        ]
+    }
 }
 
-/// Check if authentication is available and valid
-pub async fn check_auth() -> Result<AuthStatus> {
-    match get_token() {
+/// Check if authentication is available and valid for a host
+pub async fn check_auth(host: &str) -> Result<AuthStatus> {
+    match get_token(host) {
         Ok(token) => {
             // Verify token works by making a simple API call
-            let octocrab = octocrab::Octocrab::builder()
-                .personal_token(token)
-                .build()?;
+            let client = GitHubClient::with_token(host, token)?;
 
-            match octocrab.current().user().await {
-                Ok(user) => Ok(AuthStatus::Authenticated {
-                    username: user.login,
-                    source: if std::env::var("GITHUB_TOKEN").is_ok() {
-                        "GITHUB_TOKEN".to_string()
-                    } else {
-                        "gh auth token".to_string()
-                    },
+            match client.get_viewer().await {
+                Ok(username) => Ok(AuthStatus::Authenticated {
+                    username,
+                    source: token_env_vars(host)
+                        .iter()
+                        .find(|name| std::env::var(name).is_ok())
+                        .map(|name| name.to_string())
+                        .unwrap_or_else(|| "gh auth token".to_string()),
                 }),
                 Err(e) => Ok(AuthStatus::InvalidToken {
                     error: e.to_string(),
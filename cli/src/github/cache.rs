@@ -0,0 +1,136 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use super::cassette::RecordedResponse;
+
+/// A cached GET response and the ETag GitHub returned for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub url: String,
+    pub etag: String,
+    pub response: RecordedResponse,
+}
+
+/// Per-repository cache of ETag-tagged GitHub responses, stored under
+/// `.git/neo-reviewer/cache`. Conditional requests answered with 304 don't
+/// count against the rate limit, so repeated syncs stay cheap.
+pub struct ResponseCache {
+    dir: PathBuf,
+}
+
+impl ResponseCache {
+    /// Open the cache for the current repository, if we're inside one
+    pub fn open() -> Option<Self> {
+        cache_dir().ok().map(|dir| Self { dir })
+    }
+
+    pub fn get(&self, url: &str) -> Option<CacheEntry> {
+        let content = fs::read_to_string(self.entry_path(url)).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&content).ok()?;
+        // Guard against (unlikely) hash collisions between URLs.
+        (entry.url == url).then_some(entry)
+    }
+
+    /// Store a response; failures are ignored since the cache is best effort
+    pub fn put(&self, entry: &CacheEntry) {
+        if fs::create_dir_all(&self.dir).is_err() {
+            return;
+        }
+        if let Ok(content) = serde_json::to_string(entry) {
+            let _ = fs::write(self.entry_path(&entry.url), content);
+        }
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", fnv1a(url)))
+    }
+}
+
+/// Remove every cached response for the current repository, returning how
+/// many entries were deleted
+pub fn clear() -> Result<usize> {
+    let dir = cache_dir()?;
+    if !dir.exists() {
+        return Ok(0);
+    }
+
+    let removed = count_entries(&dir)?;
+    fs::remove_dir_all(&dir)
+        .with_context(|| format!("Failed to remove cache directory {}", dir.display()))?;
+    Ok(removed)
+}
+
+fn count_entries(dir: &Path) -> Result<usize> {
+    Ok(fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .count())
+}
+
+fn cache_dir() -> Result<PathBuf> {
+    // The common dir is shared between worktrees, so they share one cache.
+    let output = Command::new("git")
+        .args(["rev-parse", "--path-format=absolute", "--git-common-dir"])
+        .output()?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "Failed to locate git directory: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let git_dir = String::from_utf8(output.stdout)?.trim().to_string();
+    Ok(Path::new(&git_dir).join("neo-reviewer").join("cache"))
+}
+
+/// Stable 64-bit FNV-1a hash, used to derive cache file names from URLs
+fn fnv1a(input: &str) -> u64 {
+    input.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn temp_cache(name: &str) -> ResponseCache {
+        let dir = std::env::temp_dir().join(format!(
+            "neo-reviewer-cache-test-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        ResponseCache { dir }
+    }
+
+    #[test]
+    fn fnv1a_is_stable() {
+        assert_eq!(fnv1a(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a("a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn put_then_get_round_trips() {
+        let cache = temp_cache("round-trip");
+        let url = "https://api.github.com/repos/o/r/pulls/1";
+        cache.put(&CacheEntry {
+            url: url.to_string(),
+            etag: "W/\"abc\"".to_string(),
+            response: RecordedResponse::from_text(200, BTreeMap::new(), r#"{"number":1}"#),
+        });
+
+        let entry = cache.get(url).expect("entry should be cached");
+        assert_eq!(entry.etag, "W/\"abc\"");
+        assert_eq!(entry.response.body["number"], 1);
+        assert!(cache.get("https://api.github.com/other").is_none());
+
+        let _ = fs::remove_dir_all(&cache.dir);
+    }
+}
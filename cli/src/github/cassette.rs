@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+/// Response headers worth keeping in a cassette. Everything else (cookies,
+/// request IDs, security headers) is dropped so cassettes are safe to share.
+pub const RECORDED_HEADERS: &[&str] = &[
+    "content-type",
+    "etag",
+    "link",
+    "retry-after",
+    "x-ratelimit-limit",
+    "x-ratelimit-remaining",
+    "x-ratelimit-reset",
+];
+
+/// One recorded request/response pair, stored as a JSON file in the cassette
+/// directory. Request headers are never stored, so tokens don't leak.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// JSON bodies are stored as-is for readability; anything else as a string
+    #[serde(default)]
+    pub body: serde_json::Value,
+}
+
+impl RecordedResponse {
+    pub fn from_text(status: u16, headers: BTreeMap<String, String>, text: &str) -> Self {
+        let body = serde_json::from_str(text)
+            .unwrap_or_else(|_| serde_json::Value::String(text.to_string()));
+        Self {
+            status,
+            headers,
+            body,
+        }
+    }
+
+    pub fn body_text(&self) -> String {
+        match &self.body {
+            serde_json::Value::String(text) => text.clone(),
+            serde_json::Value::Null => String::new(),
+            other => other.to_string(),
+        }
+    }
+}
+
+/// Writes each interaction to its own numbered file in the cassette directory
+pub struct Recorder {
+    dir: PathBuf,
+    next: Mutex<usize>,
+}
+
+impl Recorder {
+    pub fn new(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create cassette directory {}", dir.display()))?;
+        let existing = cassette_files(dir)?.len();
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            next: Mutex::new(existing + 1),
+        })
+    }
+
+    pub fn record(&self, interaction: &Interaction) -> Result<()> {
+        let mut next = self
+            .next
+            .lock()
+            .map_err(|_| anyhow!("Cassette recorder lock poisoned"))?;
+        let file_name = format!(
+            "{:04}-{}.json",
+            *next,
+            interaction.request.method.to_lowercase()
+        );
+        let path = self.dir.join(file_name);
+        fs::write(&path, serde_json::to_string_pretty(interaction)?)
+            .with_context(|| format!("Failed to write cassette file {}", path.display()))?;
+        *next += 1;
+        Ok(())
+    }
+}
+
+/// Serves recorded responses for matching requests. Each interaction is used
+/// at most once, in recording order, so repeated requests (e.g. polling)
+/// replay the sequence that was captured.
+pub struct Player {
+    dir: PathBuf,
+    interactions: Mutex<Vec<(Interaction, bool)>>,
+}
+
+impl Player {
+    pub fn load(dir: &Path) -> Result<Self> {
+        let mut interactions = Vec::new();
+        for path in cassette_files(dir)? {
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read cassette file {}", path.display()))?;
+            let interaction: Interaction = serde_json::from_str(&content)
+                .with_context(|| format!("Invalid cassette file {}", path.display()))?;
+            interactions.push((interaction, false));
+        }
+
+        Ok(Self::from_interactions(dir, interactions))
+    }
+
+    fn from_interactions(dir: &Path, interactions: Vec<(Interaction, bool)>) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            interactions: Mutex::new(interactions),
+        }
+    }
+
+    pub fn play(
+        &self,
+        method: &str,
+        url: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<RecordedResponse> {
+        let mut interactions = self
+            .interactions
+            .lock()
+            .map_err(|_| anyhow!("Cassette player lock poisoned"))?;
+
+        let entry = interactions.iter_mut().find(|(interaction, used)| {
+            !used
+                && interaction.request.method.eq_ignore_ascii_case(method)
+                && interaction.request.url == url
+                && interaction.request.body.as_ref() == body
+        });
+
+        match entry {
+            Some((interaction, used)) => {
+                *used = true;
+                Ok(interaction.response.clone())
+            }
+            None => Err(anyhow!(
+                "No recorded response for {} {} in cassette {}",
+                method,
+                url,
+                self.dir.display()
+            )),
+        }
+    }
+}
+
+fn cassette_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("Failed to read cassette directory {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interaction(
+        method: &str,
+        url: &str,
+        body: Option<serde_json::Value>,
+        id: u64,
+    ) -> Interaction {
+        Interaction {
+            request: RecordedRequest {
+                method: method.to_string(),
+                url: url.to_string(),
+                body,
+            },
+            response: RecordedResponse {
+                status: 200,
+                headers: BTreeMap::new(),
+                body: serde_json::json!({ "id": id }),
+            },
+        }
+    }
+
+    fn player(interactions: Vec<Interaction>) -> Player {
+        Player::from_interactions(
+            Path::new("cassette"),
+            interactions.into_iter().map(|i| (i, false)).collect(),
+        )
+    }
+
+    #[test]
+    fn play_serves_repeated_requests_in_recorded_order() {
+        let player = player(vec![
+            interaction("GET", "https://api/x", None, 1),
+            interaction("GET", "https://api/x", None, 2),
+        ]);
+
+        let first = player.play("GET", "https://api/x", None).unwrap();
+        let second = player.play("GET", "https://api/x", None).unwrap();
+        assert_eq!(first.body["id"], 1);
+        assert_eq!(second.body["id"], 2);
+        assert!(player.play("GET", "https://api/x", None).is_err());
+    }
+
+    #[test]
+    fn play_matches_on_method_url_and_body() {
+        let body = serde_json::json!({ "body": "hi" });
+        let player = player(vec![
+            interaction("GET", "https://api/x", None, 1),
+            interaction("POST", "https://api/x", Some(body.clone()), 2),
+        ]);
+
+        let other_body = serde_json::json!({ "body": "bye" });
+        assert!(
+            player
+                .play("POST", "https://api/x", Some(&other_body))
+                .is_err()
+        );
+        assert_eq!(
+            player
+                .play("post", "https://api/x", Some(&body))
+                .unwrap()
+                .body["id"],
+            2
+        );
+    }
+
+    #[test]
+    fn recorded_response_round_trips_non_json_bodies() {
+        let response = RecordedResponse::from_text(502, BTreeMap::new(), "Bad gateway");
+        assert_eq!(response.body_text(), "Bad gateway");
+
+        let response = RecordedResponse::from_text(200, BTreeMap::new(), r#"{"a":1}"#);
+        assert_eq!(response.body_text(), r#"{"a":1}"#);
+    }
+}
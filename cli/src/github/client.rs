@@ -1,66 +1,175 @@
+use std::collections::HashMap;
+
 use anyhow::{Result, anyhow};
-use octocrab::Octocrab;
 use regex::Regex;
+use reqwest::{Method, StatusCode};
 
 use super::auth::get_token;
-use super::types::{PrRef, PullRequest, ReviewComment};
+use super::http::{self, ApiError, Transport, parse_utc};
+use super::types::{
+    Annotation, Check, CheckKind, DEFAULT_HOST, DraftReviewComment, IssueComment, MergeMethod,
+    MergeStatus, PendingReview, PrCommit, PrRef, PullRequest, ReactionContent, ReactionGroup,
+    ReviewComment, ReviewEvent, ReviewThread, Reviewer, ReviewerKind, SearchedPullRequest,
+    SubmittedReview, ViewedState,
+};
 
 /// GitHub API client wrapper
 pub struct GitHubClient {
-    octocrab: Octocrab,
-    token: String,
+    http: Transport,
+    api_base: String,
+    graphql_url: String,
 }
 
-fn extract_next_page_url(link_header: &str) -> Option<String> {
-    for segment in link_header.split(',') {
-        let part = segment.trim();
-        if !part.contains("rel=\"next\"") {
-            continue;
-        }
+/// REST API base for a GitHub host; Enterprise Server serves it under `/api/v3`
+pub fn api_base_for_host(host: &str) -> String {
+    if host == DEFAULT_HOST {
+        "https://api.github.com".to_string()
+    } else {
+        format!("https://{}/api/v3", host)
+    }
+}
 
-        let start = part.find('<')?;
-        let end = part[start + 1..].find('>')? + start + 1;
-        return Some(part[start + 1..end].to_string());
+/// GraphQL endpoint for a GitHub host; Enterprise Server serves it under `/api/graphql`
+pub fn graphql_url_for_host(host: &str) -> String {
+    if host == DEFAULT_HOST {
+        "https://api.github.com/graphql".to_string()
+    } else {
+        format!("https://{}/api/graphql", host)
     }
+}
+
+/// Percent-encode a value as a single URL path segment
+fn path_segment(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes())
+        .collect::<String>()
+        .replace('+', "%20")
+}
+
+/// Percent-encode a branch name for a URL path, keeping its `/` separators
+fn branch_path(branch: &str) -> String {
+    branch
+        .split('/')
+        .map(path_segment)
+        .collect::<Vec<_>>()
+        .join("/")
+}
 
-    None
+/// Percent-encode a query parameter value; branch names may contain `+`, `#` and `&`
+fn query_value(value: &str) -> String {
+    url::form_urlencoded::byte_serialize(value.as_bytes()).collect()
 }
 
 impl GitHubClient {
-    /// Create a new authenticated GitHub client
-    pub fn new() -> Result<Self> {
-        let token = get_token()?;
-        let octocrab = Octocrab::builder().personal_token(token.clone()).build()?;
-        Ok(Self { octocrab, token })
+    /// Create a new authenticated GitHub client for the given host
+    /// (`github.com` or a GitHub Enterprise Server hostname)
+    pub fn new(host: &str) -> Result<Self> {
+        // Replayed cassettes never reach GitHub, so they work without credentials.
+        let token = if Transport::is_replaying() {
+            String::new()
+        } else {
+            get_token(host)?
+        };
+        Self::with_token(host, token)
+    }
+
+    /// Create a client for the given host using an already resolved token
+    pub fn with_token(host: &str, token: String) -> Result<Self> {
+        let config = http::config();
+        let api_base = match &config.api_url {
+            Some(api_url) => api_url.trim_end_matches('/').to_string(),
+            None => api_base_for_host(host),
+        };
+        let graphql_url = match (&config.graphql_url, &config.api_url) {
+            (Some(graphql_url), _) => graphql_url.clone(),
+            (None, Some(_)) => format!("{}/graphql", api_base),
+            (None, None) => graphql_url_for_host(host),
+        };
+
+        Ok(Self {
+            http: Transport::new(token)?,
+            api_base,
+            graphql_url,
+        })
     }
 
-    /// Parse a GitHub PR URL into its components
+    /// Parse a GitHub (or GitHub Enterprise Server) PR URL into its components.
+    /// The scheme is optional. Any host is accepted, since an enterprise
+    /// server can live anywhere; a host that isn't GitHub fails once its
+    /// token or API is needed.
     pub fn parse_pr_url(url: &str) -> Result<PrRef> {
-        let re = Regex::new(r"github\.com/([^/]+)/([^/]+)/pull/(\d+)")?;
+        let re = Regex::new(r"^(?:https?://)?(?:www\.)?([^/\s]+)/([^/\s]+)/([^/\s]+)/pull/(\d+)")?;
         let caps = re
-            .captures(url)
+            .captures(url.trim())
             .ok_or_else(|| anyhow!("Invalid GitHub PR URL: {}", url))?;
 
         Ok(PrRef {
-            owner: caps[1].to_string(),
-            repo: caps[2].to_string(),
-            number: caps[3].parse()?,
+            host: caps[1].to_lowercase(),
+            owner: caps[2].to_string(),
+            repo: caps[3].to_string(),
+            number: caps[4].parse()?,
         })
     }
 
     /// Get the authenticated user's login
     pub async fn get_viewer(&self) -> Result<String> {
-        let user = self.octocrab.current().user().await?;
+        #[derive(serde::Deserialize)]
+        struct UserRaw {
+            login: String,
+        }
+
+        let url = format!("{}/user", self.api_base);
+        let user: UserRaw = self.http.get_json(&url, "fetch viewer").await?;
         Ok(user.login)
     }
 
     /// Fetch PR metadata
     pub async fn get_pr(&self, pr_ref: &PrRef) -> Result<PullRequest> {
-        let pr = self
-            .octocrab
-            .pulls(&pr_ref.owner, &pr_ref.repo)
-            .get(pr_ref.number)
-            .await?;
+        let url = format!(
+            "{}/repos/{}/{}/pulls/{}",
+            self.api_base, pr_ref.owner, pr_ref.repo, pr_ref.number
+        );
+
+        #[derive(serde::Deserialize)]
+        struct PullRequestRaw {
+            number: u64,
+            title: Option<String>,
+            body: Option<String>,
+            state: Option<String>,
+            user: Option<UserRaw>,
+            base: BranchRaw,
+            head: BranchRaw,
+            #[serde(default)]
+            labels: Vec<LabelRaw>,
+            #[serde(default)]
+            draft: bool,
+            milestone: Option<MilestoneRaw>,
+            #[serde(default)]
+            review_comments: u32,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct LabelRaw {
+            name: String,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct MilestoneRaw {
+            title: String,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct BranchRaw {
+            sha: String,
+            #[serde(rename = "ref")]
+            ref_field: String,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct UserRaw {
+            login: String,
+        }
+
+        let pr: PullRequestRaw = self.http.get_json(&url, "fetch PR").await?;
 
         Ok(PullRequest {
             number: pr.number,
@@ -74,31 +183,288 @@ impl GitHubClient {
             author: pr.user.map(|u| u.login).unwrap_or_default(),
             state: pr
                 .state
-                .map(|s| format!("{:?}", s).to_lowercase())
+                .map(|s| s.to_lowercase())
                 .unwrap_or_else(|| "unknown".to_string()),
+            labels: pr.labels.into_iter().map(|l| l.name).collect(),
+            draft: pr.draft,
+            milestone: pr.milestone.map(|m| m.title),
+            review_comment_count: pr.review_comments,
         })
     }
 
-    /// Fetch review comments for the PR
-    pub async fn get_review_comments(&self, pr_ref: &PrRef) -> Result<Vec<ReviewComment>> {
+    /// Update the PR's title and/or description
+    pub async fn update_pr(
+        &self,
+        pr_ref: &PrRef,
+        title: Option<&str>,
+        body: Option<&str>,
+    ) -> Result<()> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls/{}",
+            self.api_base, pr_ref.owner, pr_ref.repo, pr_ref.number
+        );
+
+        #[derive(serde::Serialize)]
+        struct UpdatePrRequest<'a> {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            title: Option<&'a str>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            body: Option<&'a str>,
+        }
+
+        self.http
+            .send_json(
+                Method::PATCH,
+                &url,
+                &UpdatePrRequest { title, body },
+                "update PR",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Add labels to the PR, keeping existing ones
+    pub async fn add_labels(&self, pr_ref: &PrRef, labels: &[String]) -> Result<()> {
+        let url = format!(
+            "{}/repos/{}/{}/issues/{}/labels",
+            self.api_base, pr_ref.owner, pr_ref.repo, pr_ref.number
+        );
+
+        #[derive(serde::Serialize)]
+        struct AddLabelsRequest<'a> {
+            labels: &'a [String],
+        }
+
+        self.http
+            .send_json(
+                Method::POST,
+                &url,
+                &AddLabelsRequest { labels },
+                "add labels",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Remove a label from the PR
+    pub async fn remove_label(&self, pr_ref: &PrRef, label: &str) -> Result<()> {
+        // Label names may contain spaces and slashes; encode them as one path segment.
+        let url = format!(
+            "{}/repos/{}/{}/issues/{}/labels/{}",
+            self.api_base,
+            pr_ref.owner,
+            pr_ref.repo,
+            pr_ref.number,
+            path_segment(label)
+        );
+
+        self.http.delete(&url, "remove label").await?;
+        Ok(())
+    }
+
+    /// Convert the PR to a draft, or mark it ready for review
+    pub async fn set_pr_draft(&self, pr_ref: &PrRef, draft: bool) -> Result<()> {
+        let (mutation, action) = if draft {
+            (
+                r#"
+                mutation($pullRequestId: ID!) {
+                  convertPullRequestToDraft(input: { pullRequestId: $pullRequestId }) {
+                    clientMutationId
+                  }
+                }
+                "#,
+                "convert PR to draft",
+            )
+        } else {
+            (
+                r#"
+                mutation($pullRequestId: ID!) {
+                  markPullRequestReadyForReview(input: { pullRequestId: $pullRequestId }) {
+                    clientMutationId
+                  }
+                }
+                "#,
+                "mark PR ready for review",
+            )
+        };
+
+        let pull_request_id = self.get_pr_node_id(pr_ref, action).await?;
+
+        let _: serde_json::Value = self
+            .graphql(
+                mutation,
+                serde_json::json!({ "pullRequestId": pull_request_id }),
+                action,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Look up the PR's GraphQL node ID, needed by mutations
+    async fn get_pr_node_id(&self, pr_ref: &PrRef, action: &str) -> Result<String> {
+        const PR_ID_QUERY: &str = r#"
+            query($owner: String!, $repo: String!, $number: Int!) {
+              repository(owner: $owner, name: $repo) {
+                pullRequest(number: $number) { id }
+              }
+            }
+        "#;
+
+        let data: serde_json::Value = self
+            .graphql(
+                PR_ID_QUERY,
+                serde_json::json!({
+                    "owner": pr_ref.owner,
+                    "repo": pr_ref.repo,
+                    "number": pr_ref.number,
+                }),
+                action,
+            )
+            .await?;
+
+        data["repository"]["pullRequest"]["id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("Failed to {}: pull request not found", action))
+    }
+
+    /// Fetch the repository's default branch name
+    pub async fn get_default_branch(&self, owner: &str, repo: &str) -> Result<String> {
+        let url = format!("{}/repos/{}/{}", self.api_base, owner, repo);
+
+        #[derive(serde::Deserialize)]
+        struct RepositoryRaw {
+            default_branch: String,
+        }
+
+        let raw: RepositoryRaw = self.http.get_json(&url, "fetch repository").await?;
+        Ok(raw.default_branch)
+    }
+
+    /// Whether the repository has a branch with the given name
+    pub async fn branch_exists(&self, owner: &str, repo: &str, branch: &str) -> Result<bool> {
+        let url = format!(
+            "{}/repos/{}/{}/branches/{}",
+            self.api_base,
+            owner,
+            repo,
+            branch_path(branch)
+        );
+
+        match self.http.get(&url, "fetch branch").await {
+            Ok(_) => Ok(true),
+            Err(e)
+                if e.downcast_ref::<ApiError>()
+                    .is_some_and(|api| api.status == StatusCode::NOT_FOUND) =>
+            {
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Number of the open PR from `head` (`OWNER:BRANCH`), if there is one
+    pub async fn find_open_pr(&self, owner: &str, repo: &str, head: &str) -> Result<Option<u64>> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls?head={}&state=open&per_page=1",
+            self.api_base,
+            owner,
+            repo,
+            query_value(head)
+        );
+
+        #[derive(serde::Deserialize)]
+        struct PullRaw {
+            number: u64,
+        }
+
+        let pulls: Vec<PullRaw> = self.http.get_json(&url, "fetch open PRs").await?;
+        Ok(pulls.first().map(|pr| pr.number))
+    }
+
+    /// Open a pull request from `head` into `base`, returning its number
+    #[allow(clippy::too_many_arguments)]
+    pub async fn create_pr(
+        &self,
+        owner: &str,
+        repo: &str,
+        head: &str,
+        base: &str,
+        title: &str,
+        body: Option<&str>,
+        draft: bool,
+    ) -> Result<u64> {
+        let url = format!("{}/repos/{}/{}/pulls", self.api_base, owner, repo);
+
+        #[derive(serde::Serialize)]
+        struct CreatePrRequest<'a> {
+            head: &'a str,
+            base: &'a str,
+            title: &'a str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            body: Option<&'a str>,
+            draft: bool,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct CreatedPrRaw {
+            number: u64,
+        }
+
+        let response = self
+            .http
+            .send_json(
+                Method::POST,
+                &url,
+                &CreatePrRequest {
+                    head,
+                    base,
+                    title,
+                    body,
+                    draft,
+                },
+                "create PR",
+            )
+            .await?;
+
+        let raw: CreatedPrRaw = response.json()?;
+        Ok(raw.number)
+    }
+
+    /// Fetch the PR's commits, oldest first
+    pub async fn get_pr_commits(&self, pr_ref: &PrRef) -> Result<Vec<PrCommit>> {
         let initial_url = format!(
-            "https://api.github.com/repos/{}/{}/pulls/{}/comments?per_page=100",
-            pr_ref.owner, pr_ref.repo, pr_ref.number
+            "{}/repos/{}/{}/pulls/{}/commits?per_page=100",
+            self.api_base, pr_ref.owner, pr_ref.repo, pr_ref.number
         );
 
         #[derive(serde::Deserialize)]
-        struct CommentRaw {
-            id: u64,
-            path: String,
-            line: Option<u32>,
-            start_line: Option<u32>,
-            side: Option<String>,
-            start_side: Option<String>,
-            body: String,
-            user: Option<UserRaw>,
-            created_at: String,
-            html_url: String,
-            in_reply_to_id: Option<u64>,
+        struct CommitRaw {
+            sha: String,
+            commit: CommitDetailsRaw,
+            author: Option<UserRaw>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct CommitDetailsRaw {
+            message: String,
+            author: Option<GitAuthorRaw>,
+            verification: Option<VerificationRaw>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct GitAuthorRaw {
+            name: Option<String>,
+            date: Option<String>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct VerificationRaw {
+            verified: bool,
+            reason: Option<String>,
         }
 
         #[derive(serde::Deserialize)]
@@ -106,104 +472,186 @@ impl GitHubClient {
             login: String,
         }
 
-        let client = reqwest::Client::new();
-        let mut next_page_url = Some(initial_url);
-        let mut raw_comments = Vec::new();
+        let raw_commits: Vec<CommitRaw> = self
+            .http
+            .get_paginated(&initial_url, "fetch commits")
+            .await?;
 
-        while let Some(page_url) = next_page_url {
-            let response = client
-                .get(&page_url)
-                .header("Authorization", format!("Bearer {}", self.token))
-                .header("Accept", "application/vnd.github+json")
-                .header("User-Agent", "neo-reviewer")
-                .header("X-GitHub-Api-Version", "2022-11-28")
-                .send()
-                .await?;
+        Ok(raw_commits
+            .into_iter()
+            .map(|c| {
+                let git_author = c.commit.author;
+                let verification = c.commit.verification;
+                PrCommit {
+                    sha: c.sha,
+                    message: c.commit.message,
+                    author: c
+                        .author
+                        .map(|u| u.login)
+                        .or_else(|| git_author.as_ref().and_then(|a| a.name.clone()))
+                        .unwrap_or_default(),
+                    authored_at: git_author.and_then(|a| a.date),
+                    verified: verification.as_ref().is_some_and(|v| v.verified),
+                    verification_reason: verification.and_then(|v| v.reason),
+                }
+            })
+            .collect())
+    }
 
-            if !response.status().is_success() {
-                let status = response.status();
-                let error_body = response.text().await.unwrap_or_default();
-                return Err(anyhow!(
-                    "Failed to fetch comments: {} - {}",
-                    status,
-                    error_body
-                ));
-            }
+    /// Fetch review comments for the PR
+    pub async fn get_review_comments(&self, pr_ref: &PrRef) -> Result<Vec<ReviewComment>> {
+        self.list_review_comments(pr_ref, None, true).await
+    }
 
-            let next_from_link_header = response
-                .headers()
-                .get(reqwest::header::LINK)
-                .and_then(|value| value.to_str().ok())
-                .and_then(extract_next_page_url);
+    /// Fetch review comments without reactions, optionally only those created
+    /// or edited at or after `since`. Cheap enough to poll.
+    pub async fn get_review_comments_since(
+        &self,
+        pr_ref: &PrRef,
+        since: Option<&str>,
+    ) -> Result<Vec<ReviewComment>> {
+        self.list_review_comments(pr_ref, since, false).await
+    }
 
-            let mut page_comments: Vec<CommentRaw> = response.json().await?;
-            raw_comments.append(&mut page_comments);
-            next_page_url = next_from_link_header;
+    async fn list_review_comments(
+        &self,
+        pr_ref: &PrRef,
+        since: Option<&str>,
+        with_reactions: bool,
+    ) -> Result<Vec<ReviewComment>> {
+        let mut initial_url = format!(
+            "{}/repos/{}/{}/pulls/{}/comments?per_page=100",
+            self.api_base, pr_ref.owner, pr_ref.repo, pr_ref.number
+        );
+        if let Some(since) = since {
+            initial_url.push_str(&format!("&since={since}"));
         }
 
+        let raw_comments: Vec<ReviewCommentRaw> = self
+            .http
+            .get_paginated(&initial_url, "fetch comments")
+            .await?;
+
+        let mut reactions = if with_reactions {
+            let node_ids: Vec<String> = raw_comments.iter().map(|c| c.node_id.clone()).collect();
+            self.get_comment_reactions(&node_ids).await?
+        } else {
+            HashMap::new()
+        };
+
         let review_comments: Vec<ReviewComment> = raw_comments
             .into_iter()
-            .map(|c| ReviewComment {
-                id: c.id,
-                path: c.path,
-                line: c.line,
-                start_line: c.start_line,
-                side: c.side.unwrap_or_default(),
-                start_side: c.start_side,
-                body: c.body,
-                author: c.user.map(|u| u.login).unwrap_or_default(),
-                created_at: c.created_at,
-                html_url: c.html_url,
-                in_reply_to_id: c.in_reply_to_id,
+            .map(|c| {
+                let mut comment = review_comment(c);
+                comment.reactions = reactions.remove(&comment.id).unwrap_or_default();
+                comment
             })
             .collect();
 
         Ok(review_comments)
     }
 
-    /// Add a review comment to a specific line or line range using raw API
-    #[allow(clippy::too_many_arguments)]
-    pub async fn add_review_comment(
+    /// Look up reactions (including the viewer's own) for review comments by
+    /// node ID, keyed by comment ID. The REST API only reports counts.
+    async fn get_comment_reactions(
+        &self,
+        node_ids: &[String],
+    ) -> Result<HashMap<u64, Vec<ReactionGroup>>> {
+        const QUERY: &str = r#"
+            query($ids: [ID!]!) {
+              nodes(ids: $ids) {
+                ... on PullRequestReviewComment {
+                  databaseId
+                  reactionGroups { content viewerHasReacted reactors { totalCount } }
+                }
+              }
+            }
+        "#;
+
+        #[derive(serde::Deserialize)]
+        struct Data {
+            nodes: Vec<Option<NodeRaw>>,
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct NodeRaw {
+            database_id: Option<u64>,
+            reaction_groups: Option<Vec<ReactionGroupRaw>>,
+        }
+
+        let mut reactions = HashMap::new();
+        // GraphQL caps `nodes` lookups at 100 IDs per request.
+        for chunk in node_ids.chunks(100) {
+            let data: Data = self
+                .graphql(
+                    QUERY,
+                    serde_json::json!({ "ids": chunk }),
+                    "fetch comment reactions",
+                )
+                .await?;
+
+            for node in data.nodes.into_iter().flatten() {
+                if let Some(id) = node.database_id {
+                    let groups = reaction_groups(node.reaction_groups.unwrap_or_default());
+                    reactions.insert(id, groups);
+                }
+            }
+        }
+
+        Ok(reactions)
+    }
+
+    /// React to a review comment; reacting twice with the same content is a no-op
+    pub async fn add_reaction(
         &self,
         pr_ref: &PrRef,
-        head_sha: &str,
-        path: &str,
-        line: u32,
-        side: &str,
-        body: &str,
-        start_line: Option<u32>,
-        start_side: Option<&str>,
-    ) -> Result<ReviewComment> {
+        comment_id: u64,
+        content: ReactionContent,
+    ) -> Result<()> {
         let url = format!(
-            "https://api.github.com/repos/{}/{}/pulls/{}/comments",
-            pr_ref.owner, pr_ref.repo, pr_ref.number
+            "{}/repos/{}/{}/pulls/comments/{}/reactions",
+            self.api_base, pr_ref.owner, pr_ref.repo, comment_id
         );
 
         #[derive(serde::Serialize)]
-        struct CommentRequest {
-            body: String,
-            commit_id: String,
-            path: String,
-            line: u32,
-            side: String,
-            #[serde(skip_serializing_if = "Option::is_none")]
-            start_line: Option<u32>,
-            #[serde(skip_serializing_if = "Option::is_none")]
-            start_side: Option<String>,
+        struct ReactionRequest {
+            content: ReactionContent,
         }
 
+        self.http
+            .send_json(
+                Method::POST,
+                &url,
+                &ReactionRequest { content },
+                "add reaction",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Remove the viewer's reaction from a review comment, if present
+    pub async fn remove_reaction(
+        &self,
+        pr_ref: &PrRef,
+        comment_id: u64,
+        content: ReactionContent,
+    ) -> Result<()> {
+        let base_url = format!(
+            "{}/repos/{}/{}/pulls/comments/{}/reactions",
+            self.api_base, pr_ref.owner, pr_ref.repo, comment_id
+        );
+        let list_url = format!(
+            "{}?content={}&per_page=100",
+            base_url,
+            content.as_str().replace('+', "%2B")
+        );
+
         #[derive(serde::Deserialize)]
-        struct CommentResponseRaw {
+        struct ReactionRaw {
             id: u64,
-            path: Option<String>,
-            line: Option<u32>,
-            start_line: Option<u32>,
-            side: Option<String>,
-            start_side: Option<String>,
-            body: Option<String>,
             user: Option<UserRaw>,
-            created_at: Option<String>,
-            html_url: Option<String>,
         }
 
         #[derive(serde::Deserialize)]
@@ -211,155 +659,1428 @@ impl GitHubClient {
             login: String,
         }
 
-        let client = reqwest::Client::new();
-        let response = client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.token))
-            .header("Accept", "application/vnd.github+json")
-            .header("User-Agent", "neo-reviewer")
-            .header("X-GitHub-Api-Version", "2022-11-28")
-            .json(&CommentRequest {
-                body: body.to_string(),
-                commit_id: head_sha.to_string(),
-                path: path.to_string(),
-                line,
-                side: side.to_uppercase(),
-                start_line,
-                start_side: start_side.map(|s| s.to_uppercase()),
-            })
-            .send()
-            .await?;
+        // Deleting requires the reaction ID, so find the viewer's own reaction.
+        let (viewer, reactions) = tokio::try_join!(
+            self.get_viewer(),
+            self.http
+                .get_paginated::<ReactionRaw>(&list_url, "fetch reactions")
+        )?;
+
+        let own = reactions
+            .into_iter()
+            .find(|r| r.user.as_ref().is_some_and(|u| u.login == viewer));
+
+        if let Some(reaction) = own {
+            let url = format!("{}/{}", base_url, reaction.id);
+            self.http.delete(&url, "remove reaction").await?;
+        }
+
+        Ok(())
+    }
+
+    /// Fetch check runs and legacy commit statuses for a commit
+    pub async fn get_checks(&self, pr_ref: &PrRef, sha: &str) -> Result<Vec<Check>> {
+        let check_runs_url = format!(
+            "{}/repos/{}/{}/commits/{}/check-runs?per_page=100",
+            self.api_base, pr_ref.owner, pr_ref.repo, sha
+        );
+        let statuses_url = format!(
+            "{}/repos/{}/{}/commits/{}/status?per_page=100",
+            self.api_base, pr_ref.owner, pr_ref.repo, sha
+        );
+
+        #[derive(serde::Deserialize)]
+        struct CheckRunRaw {
+            id: u64,
+            name: String,
+            conclusion: Option<String>,
+            started_at: Option<String>,
+            completed_at: Option<String>,
+            details_url: Option<String>,
+            html_url: Option<String>,
+            output: Option<OutputRaw>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct OutputRaw {
+            #[serde(default)]
+            annotations_count: u32,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct StatusRaw {
+            id: u64,
+            context: String,
+            state: String,
+            target_url: Option<String>,
+            created_at: Option<String>,
+            updated_at: Option<String>,
+        }
+
+        // The combined status endpoint already reduces statuses to the latest per context.
+        let (check_runs, statuses) = tokio::try_join!(
+            self.http.get_paginated_field::<CheckRunRaw>(
+                &check_runs_url,
+                "check_runs",
+                "fetch check runs"
+            ),
+            self.http
+                .get_paginated_field::<StatusRaw>(&statuses_url, "statuses", "fetch statuses")
+        )?;
+
+        let check_runs = check_runs.into_iter().map(|run| Check {
+            duration_seconds: duration_between(
+                run.started_at.as_deref(),
+                run.completed_at.as_deref(),
+            ),
+            id: run.id,
+            name: run.name,
+            kind: CheckKind::CheckRun,
+            conclusion: run.conclusion,
+            started_at: run.started_at,
+            completed_at: run.completed_at,
+            details_url: run.details_url.or(run.html_url),
+            annotations_count: run.output.map(|o| o.annotations_count).unwrap_or_default(),
+        });
+
+        let statuses = statuses.into_iter().map(|status| {
+            let conclusion = (status.state != "pending").then_some(status.state);
+            let completed_at = conclusion.as_ref().and(status.updated_at);
+            Check {
+                duration_seconds: duration_between(
+                    status.created_at.as_deref(),
+                    completed_at.as_deref(),
+                ),
+                id: status.id,
+                name: status.context,
+                kind: CheckKind::Status,
+                conclusion,
+                started_at: status.created_at,
+                completed_at,
+                details_url: status.target_url,
+                annotations_count: 0,
+            }
+        });
+
+        Ok(check_runs.chain(statuses).collect())
+    }
+
+    /// Fetch annotations published by the given check runs. Runs without
+    /// annotations are skipped, so this is usually no requests at all.
+    pub async fn get_check_annotations(
+        &self,
+        pr_ref: &PrRef,
+        checks: &[Check],
+    ) -> Result<Vec<Annotation>> {
+        #[derive(serde::Deserialize)]
+        struct AnnotationRaw {
+            path: String,
+            start_line: u32,
+            end_line: u32,
+            annotation_level: Option<String>,
+            title: Option<String>,
+            message: Option<String>,
+        }
+
+        let mut annotations = Vec::new();
+        let with_annotations = checks
+            .iter()
+            .filter(|c| c.kind == CheckKind::CheckRun && c.annotations_count > 0);
+
+        for check in with_annotations {
+            let url = format!(
+                "{}/repos/{}/{}/check-runs/{}/annotations?per_page=100",
+                self.api_base, pr_ref.owner, pr_ref.repo, check.id
+            );
+            let raw: Vec<AnnotationRaw> = self
+                .http
+                .get_paginated(&url, "fetch check annotations")
+                .await?;
+
+            annotations.extend(raw.into_iter().map(|a| Annotation {
+                check_name: check.name.clone(),
+                path: a.path,
+                start_line: a.start_line,
+                end_line: a.end_line.max(a.start_line),
+                level: a.annotation_level.unwrap_or_else(|| "notice".to_string()),
+                title: a.title.filter(|t| !t.is_empty()),
+                message: a.message.unwrap_or_default(),
+                outside_diff: false,
+            }));
+        }
+
+        Ok(annotations)
+    }
+
+    /// Fetch top-level conversation comments on the PR
+    pub async fn get_issue_comments(&self, pr_ref: &PrRef) -> Result<Vec<IssueComment>> {
+        let initial_url = format!(
+            "{}/repos/{}/{}/issues/{}/comments?per_page=100",
+            self.api_base, pr_ref.owner, pr_ref.repo, pr_ref.number
+        );
+
+        #[derive(serde::Deserialize)]
+        struct IssueCommentRaw {
+            id: u64,
+            body: Option<String>,
+            user: Option<UserRaw>,
+            created_at: String,
+            html_url: String,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct UserRaw {
+            login: String,
+        }
+
+        let raw_comments: Vec<IssueCommentRaw> = self
+            .http
+            .get_paginated(&initial_url, "fetch conversation comments")
+            .await?;
+
+        Ok(raw_comments
+            .into_iter()
+            .map(|c| IssueComment {
+                id: c.id,
+                body: c.body.unwrap_or_default(),
+                author: c.user.map(|u| u.login).unwrap_or_default(),
+                created_at: c.created_at,
+                html_url: c.html_url,
+            })
+            .collect())
+    }
+
+    /// Post a top-level conversation comment on the PR
+    pub async fn add_issue_comment(&self, pr_ref: &PrRef, body: &str) -> Result<IssueComment> {
+        let url = format!(
+            "{}/repos/{}/{}/issues/{}/comments",
+            self.api_base, pr_ref.owner, pr_ref.repo, pr_ref.number
+        );
+
+        #[derive(serde::Serialize)]
+        struct IssueCommentRequest {
+            body: String,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct IssueCommentRaw {
+            id: u64,
+            body: Option<String>,
+            user: Option<UserRaw>,
+            created_at: String,
+            html_url: String,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct UserRaw {
+            login: String,
+        }
+
+        let response = self
+            .http
+            .send_json(
+                Method::POST,
+                &url,
+                &IssueCommentRequest {
+                    body: body.to_string(),
+                },
+                "create conversation comment",
+            )
+            .await?;
+
+        let raw: IssueCommentRaw = response.json()?;
+
+        Ok(IssueComment {
+            id: raw.id,
+            body: raw.body.unwrap_or_default(),
+            author: raw.user.map(|u| u.login).unwrap_or_default(),
+            created_at: raw.created_at,
+            html_url: raw.html_url,
+        })
+    }
+
+    /// Fetch submitted reviews for the PR, oldest first.
+    ///
+    /// The viewer's own pending review is skipped; it has no summary yet.
+    pub async fn get_reviews(&self, pr_ref: &PrRef) -> Result<Vec<SubmittedReview>> {
+        let initial_url = format!(
+            "{}/repos/{}/{}/pulls/{}/reviews?per_page=100",
+            self.api_base, pr_ref.owner, pr_ref.repo, pr_ref.number
+        );
+
+        #[derive(serde::Deserialize)]
+        struct ReviewRaw {
+            id: u64,
+            user: Option<UserRaw>,
+            state: String,
+            submitted_at: Option<String>,
+            body: Option<String>,
+            html_url: Option<String>,
+            commit_id: Option<String>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct UserRaw {
+            login: String,
+        }
+
+        let reviews: Vec<ReviewRaw> = self
+            .http
+            .get_paginated(&initial_url, "fetch reviews")
+            .await?;
+
+        Ok(reviews
+            .into_iter()
+            .filter(|r| r.state != "PENDING")
+            .map(|r| SubmittedReview {
+                id: r.id,
+                author: r.user.map(|u| u.login).unwrap_or_default(),
+                state: r.state,
+                submitted_at: r.submitted_at,
+                body: r.body.unwrap_or_default(),
+                html_url: r.html_url.unwrap_or_default(),
+                commit_id: r.commit_id,
+            })
+            .collect())
+    }
+
+    /// Add a review comment to a specific line or line range using raw API
+    #[allow(clippy::too_many_arguments)]
+    pub async fn add_review_comment(
+        &self,
+        pr_ref: &PrRef,
+        head_sha: &str,
+        path: &str,
+        line: u32,
+        side: &str,
+        body: &str,
+        start_line: Option<u32>,
+        start_side: Option<&str>,
+    ) -> Result<ReviewComment> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls/{}/comments",
+            self.api_base, pr_ref.owner, pr_ref.repo, pr_ref.number
+        );
+
+        #[derive(serde::Serialize)]
+        struct CommentRequest {
+            body: String,
+            commit_id: String,
+            path: String,
+            line: u32,
+            side: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            start_line: Option<u32>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            start_side: Option<String>,
+        }
+
+        let response = self
+            .http
+            .send_json(
+                Method::POST,
+                &url,
+                &CommentRequest {
+                    body: body.to_string(),
+                    commit_id: head_sha.to_string(),
+                    path: path.to_string(),
+                    line,
+                    side: side.to_uppercase(),
+                    start_line,
+                    start_side: start_side.map(|s| s.to_uppercase()),
+                },
+                "create comment",
+            )
+            .await?;
+
+        Ok(review_comment(response.json()?))
+    }
+
+    pub async fn reply_to_comment(
+        &self,
+        pr_ref: &PrRef,
+        comment_id: u64,
+        body: &str,
+    ) -> Result<ReviewComment> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls/{}/comments/{}/replies",
+            self.api_base, pr_ref.owner, pr_ref.repo, pr_ref.number, comment_id
+        );
+
+        #[derive(serde::Serialize)]
+        struct ReplyRequest {
+            body: String,
+        }
+
+        let response = self
+            .http
+            .send_json(
+                Method::POST,
+                &url,
+                &ReplyRequest {
+                    body: body.to_string(),
+                },
+                "reply to comment",
+            )
+            .await?;
+
+        Ok(review_comment(response.json()?))
+    }
+
+    pub async fn edit_review_comment(
+        &self,
+        pr_ref: &PrRef,
+        comment_id: u64,
+        body: &str,
+    ) -> Result<ReviewComment> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls/comments/{}",
+            self.api_base, pr_ref.owner, pr_ref.repo, comment_id
+        );
+
+        #[derive(serde::Serialize)]
+        struct EditRequest {
+            body: String,
+        }
+
+        let response = self
+            .http
+            .send_json(
+                Method::PATCH,
+                &url,
+                &EditRequest {
+                    body: body.to_string(),
+                },
+                "edit comment",
+            )
+            .await?;
+
+        Ok(review_comment(response.json()?))
+    }
+
+    pub async fn delete_review_comment(&self, pr_ref: &PrRef, comment_id: u64) -> Result<()> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls/comments/{}",
+            self.api_base, pr_ref.owner, pr_ref.repo, comment_id
+        );
+
+        self.http.delete(&url, "delete comment").await?;
+
+        Ok(())
+    }
+
+    /// Submit a review, including any inline comments in the same request so a
+    /// single bad anchor rejects the whole review
+    pub async fn submit_review(
+        &self,
+        pr_ref: &PrRef,
+        event: ReviewEvent,
+        body: Option<&str>,
+        comments: &[DraftReviewComment],
+    ) -> Result<()> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls/{}/reviews",
+            self.api_base, pr_ref.owner, pr_ref.repo, pr_ref.number
+        );
+
+        #[derive(serde::Serialize)]
+        struct ReviewRequest<'a> {
+            event: ReviewEvent,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            body: Option<String>,
+            #[serde(skip_serializing_if = "<[_]>::is_empty")]
+            comments: &'a [DraftReviewComment],
+        }
+
+        self.http
+            .send_json(
+                Method::POST,
+                &url,
+                &ReviewRequest {
+                    event,
+                    body: body.map(|s| s.to_string()),
+                    comments,
+                },
+                "submit review",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Fetch users and teams whose review is currently requested
+    pub async fn get_requested_reviewers(&self, pr_ref: &PrRef) -> Result<Vec<Reviewer>> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls/{}/requested_reviewers",
+            self.api_base, pr_ref.owner, pr_ref.repo, pr_ref.number
+        );
+
+        #[derive(serde::Deserialize)]
+        struct RequestedRaw {
+            #[serde(default)]
+            users: Vec<UserRaw>,
+            #[serde(default)]
+            teams: Vec<TeamRaw>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct UserRaw {
+            login: String,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct TeamRaw {
+            slug: String,
+        }
+
+        let raw: RequestedRaw = self
+            .http
+            .get_json(&url, "fetch requested reviewers")
+            .await?;
+
+        let pending = |name: String, kind: ReviewerKind| Reviewer {
+            name,
+            kind,
+            state: None,
+            submitted_at: None,
+        };
+
+        Ok(raw
+            .users
+            .into_iter()
+            .map(|u| pending(u.login, ReviewerKind::User))
+            .chain(
+                raw.teams
+                    .into_iter()
+                    .map(|t| pending(t.slug, ReviewerKind::Team)),
+            )
+            .collect())
+    }
+
+    /// Request (or re-request) review from users and teams (by slug)
+    pub async fn request_reviewers(
+        &self,
+        pr_ref: &PrRef,
+        users: &[String],
+        teams: &[String],
+    ) -> Result<()> {
+        self.update_requested_reviewers(pr_ref, Method::POST, users, teams, "request reviewers")
+            .await
+    }
+
+    /// Withdraw review requests from users and teams (by slug)
+    pub async fn remove_requested_reviewers(
+        &self,
+        pr_ref: &PrRef,
+        users: &[String],
+        teams: &[String],
+    ) -> Result<()> {
+        self.update_requested_reviewers(pr_ref, Method::DELETE, users, teams, "remove reviewers")
+            .await
+    }
+
+    async fn update_requested_reviewers(
+        &self,
+        pr_ref: &PrRef,
+        method: Method,
+        users: &[String],
+        teams: &[String],
+        action: &str,
+    ) -> Result<()> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls/{}/requested_reviewers",
+            self.api_base, pr_ref.owner, pr_ref.repo, pr_ref.number
+        );
+
+        #[derive(serde::Serialize)]
+        struct ReviewersRequest<'a> {
+            reviewers: &'a [String],
+            team_reviewers: &'a [String],
+        }
+
+        self.http
+            .send_json(
+                method,
+                &url,
+                &ReviewersRequest {
+                    reviewers: users,
+                    team_reviewers: teams,
+                },
+                action,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Find the viewer's pending review on the PR, if one exists.
+    ///
+    /// GitHub only exposes pending reviews to their author and allows at most
+    /// one per user per PR, so the first pending entry is the viewer's.
+    pub async fn get_pending_review(&self, pr_ref: &PrRef) -> Result<Option<PendingReview>> {
+        let initial_url = format!(
+            "{}/repos/{}/{}/pulls/{}/reviews?per_page=100",
+            self.api_base, pr_ref.owner, pr_ref.repo, pr_ref.number
+        );
+
+        #[derive(serde::Deserialize)]
+        struct ReviewRaw {
+            id: u64,
+            node_id: String,
+            state: String,
+            commit_id: Option<String>,
+        }
+
+        let reviews: Vec<ReviewRaw> = self
+            .http
+            .get_paginated(&initial_url, "fetch reviews")
+            .await?;
+
+        Ok(reviews
+            .into_iter()
+            .find(|r| r.state == "PENDING")
+            .map(|review| PendingReview {
+                id: review.id,
+                node_id: review.node_id,
+                commit_id: review.commit_id,
+            }))
+    }
+
+    /// Start a pending review anchored to the given head commit
+    pub async fn start_pending_review(
+        &self,
+        pr_ref: &PrRef,
+        head_sha: &str,
+    ) -> Result<PendingReview> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls/{}/reviews",
+            self.api_base, pr_ref.owner, pr_ref.repo, pr_ref.number
+        );
+
+        // Omitting `event` leaves the review in the PENDING state.
+        #[derive(serde::Serialize)]
+        struct StartReviewRequest {
+            commit_id: String,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct ReviewRaw {
+            id: u64,
+            node_id: String,
+            commit_id: Option<String>,
+        }
+
+        let response = self
+            .http
+            .send_json(
+                Method::POST,
+                &url,
+                &StartReviewRequest {
+                    commit_id: head_sha.to_string(),
+                },
+                "start review",
+            )
+            .await?;
+
+        let raw: ReviewRaw = response.json()?;
+
+        Ok(PendingReview {
+            id: raw.id,
+            node_id: raw.node_id,
+            commit_id: raw.commit_id,
+        })
+    }
+
+    /// Queue a comment on a pending review without notifying anyone
+    #[allow(clippy::too_many_arguments)]
+    pub async fn add_pending_review_comment(
+        &self,
+        review: &PendingReview,
+        path: &str,
+        line: u32,
+        side: &str,
+        body: &str,
+        start_line: Option<u32>,
+        start_side: Option<&str>,
+    ) -> Result<ReviewComment> {
+        // The REST comments endpoint publishes immediately, so queued comments
+        // have to go through GraphQL against the review's node ID.
+        const MUTATION: &str = r#"
+            mutation($input: AddPullRequestReviewThreadInput!) {
+              addPullRequestReviewThread(input: $input) {
+                thread {
+                  path
+                  line
+                  startLine
+                  diffSide
+                  startDiffSide
+                  comments(first: 1) {
+                    nodes { databaseId body url createdAt author { login } }
+                  }
+                }
+              }
+            }
+        "#;
+
+        #[derive(serde::Deserialize)]
+        struct Data {
+            #[serde(rename = "addPullRequestReviewThread")]
+            add_thread: AddThreadPayload,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct AddThreadPayload {
+            thread: Option<ThreadRaw>,
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ThreadRaw {
+            path: String,
+            line: Option<u32>,
+            start_line: Option<u32>,
+            diff_side: Option<String>,
+            start_diff_side: Option<String>,
+            comments: CommentConnection,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct CommentConnection {
+            nodes: Vec<CommentRaw>,
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct CommentRaw {
+            database_id: Option<u64>,
+            body: String,
+            url: String,
+            created_at: String,
+            author: Option<UserRaw>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct UserRaw {
+            login: String,
+        }
+
+        let mut input = serde_json::json!({
+            "pullRequestReviewId": review.node_id,
+            "path": path,
+            "line": line,
+            "side": side.to_uppercase(),
+            "body": body,
+        });
+        if let Some(start_line) = start_line {
+            input["startLine"] = start_line.into();
+        }
+        if let Some(start_side) = start_side {
+            input["startSide"] = start_side.to_uppercase().into();
+        }
+
+        let data: Data = self
+            .graphql(
+                MUTATION,
+                serde_json::json!({ "input": input }),
+                "add comment to pending review",
+            )
+            .await?;
+
+        let thread = data
+            .add_thread
+            .thread
+            .ok_or_else(|| anyhow!("Failed to add comment to pending review: no thread"))?;
+        let comment = thread
+            .comments
+            .nodes
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow!("Failed to add comment to pending review: no comment"))?;
+
+        Ok(ReviewComment {
+            id: comment.database_id.unwrap_or_default(),
+            path: thread.path,
+            line: thread.line,
+            start_line: thread.start_line,
+            side: thread.diff_side.unwrap_or_default(),
+            start_side: thread.start_diff_side,
+            body: comment.body,
+            author: comment.author.map(|u| u.login).unwrap_or_default(),
+            created_at: comment.created_at,
+            html_url: comment.url,
+            original_line: thread.line,
+            ..Default::default()
+        })
+    }
+
+    /// Delete a pending review along with all of its queued comments
+    pub async fn discard_pending_review(&self, pr_ref: &PrRef, review_id: u64) -> Result<()> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls/{}/reviews/{}",
+            self.api_base, pr_ref.owner, pr_ref.repo, pr_ref.number, review_id
+        );
+
+        self.http.delete(&url, "discard review").await?;
+
+        Ok(())
+    }
+
+    /// Submit a pending review, publishing all of its queued comments at once
+    pub async fn submit_pending_review(
+        &self,
+        pr_ref: &PrRef,
+        review_id: u64,
+        event: ReviewEvent,
+        body: Option<&str>,
+    ) -> Result<()> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls/{}/reviews/{}/events",
+            self.api_base, pr_ref.owner, pr_ref.repo, pr_ref.number, review_id
+        );
+
+        #[derive(serde::Serialize)]
+        struct SubmitRequest {
+            event: ReviewEvent,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            body: Option<String>,
+        }
+
+        self.http
+            .send_json(
+                Method::POST,
+                &url,
+                &SubmitRequest {
+                    event,
+                    body: body.map(|s| s.to_string()),
+                },
+                "submit review",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Fetch review threads with their resolution and outdated state
+    pub async fn get_review_threads(&self, pr_ref: &PrRef) -> Result<Vec<ReviewThread>> {
+        const QUERY: &str = r#"
+            query($owner: String!, $repo: String!, $number: Int!, $cursor: String) {
+              repository(owner: $owner, name: $repo) {
+                pullRequest(number: $number) {
+                  reviewThreads(first: 100, after: $cursor) {
+                    pageInfo { hasNextPage endCursor }
+                    nodes {
+                      id
+                      path
+                      line
+                      startLine
+                      diffSide
+                      isResolved
+                      isOutdated
+                      resolvedBy { login }
+                      comments(first: 100) {
+                        pageInfo { hasNextPage endCursor }
+                        nodes {
+                          databaseId
+                          body
+                          url
+                          createdAt
+                          author { login }
+                          replyTo { databaseId }
+                          originalLine
+                          commit { oid }
+                          originalCommit { oid }
+                          diffHunk
+                          reactionGroups { content viewerHasReacted reactors { totalCount } }
+                        }
+                      }
+                    }
+                  }
+                }
+              }
+            }
+        "#;
+
+        #[derive(serde::Deserialize)]
+        struct Data {
+            repository: Option<RepositoryRaw>,
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct RepositoryRaw {
+            pull_request: Option<PullRequestRaw>,
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct PullRequestRaw {
+            review_threads: ThreadConnection,
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct ThreadConnection {
+            page_info: PageInfoRaw,
+            nodes: Vec<ReviewThreadRaw>,
+        }
+
+        let mut threads = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let data: Data = self
+                .graphql(
+                    QUERY,
+                    serde_json::json!({
+                        "owner": pr_ref.owner,
+                        "repo": pr_ref.repo,
+                        "number": pr_ref.number,
+                        "cursor": cursor,
+                    }),
+                    "fetch review threads",
+                )
+                .await?;
+
+            let connection = data
+                .repository
+                .and_then(|r| r.pull_request)
+                .map(|pr| pr.review_threads)
+                .ok_or_else(|| anyhow!("Failed to fetch review threads: pull request not found"))?;
+
+            for mut thread in connection.nodes {
+                // Long discussions continue past the first page of comments.
+                while thread.comments.page_info.has_next_page {
+                    let page = self
+                        .get_thread_comments(
+                            &thread.id,
+                            thread.comments.page_info.end_cursor.take(),
+                        )
+                        .await?;
+                    thread.comments.append(page);
+                }
+                threads.push(review_thread(thread));
+            }
+
+            if !connection.page_info.has_next_page {
+                break;
+            }
+            cursor = connection.page_info.end_cursor;
+        }
+
+        Ok(threads)
+    }
+
+    /// Fetch a page of a review thread's comments after `cursor`
+    async fn get_thread_comments(
+        &self,
+        thread_id: &str,
+        cursor: Option<String>,
+    ) -> Result<ThreadCommentsRaw> {
+        const QUERY: &str = r#"
+            query($threadId: ID!, $cursor: String) {
+              node(id: $threadId) {
+                ... on PullRequestReviewThread {
+                  comments(first: 100, after: $cursor) {
+                    pageInfo { hasNextPage endCursor }
+                    nodes {
+                      databaseId
+                      body
+                      url
+                      createdAt
+                      author { login }
+                      replyTo { databaseId }
+                      originalLine
+                      commit { oid }
+                      originalCommit { oid }
+                      diffHunk
+                      reactionGroups { content viewerHasReacted reactors { totalCount } }
+                    }
+                  }
+                }
+              }
+            }
+        "#;
+
+        #[derive(serde::Deserialize)]
+        struct Data {
+            node: Option<ThreadNodeRaw>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct ThreadNodeRaw {
+            comments: ThreadCommentsRaw,
+        }
+
+        let data: Data = self
+            .graphql(
+                QUERY,
+                serde_json::json!({ "threadId": thread_id, "cursor": cursor }),
+                "fetch review thread comments",
+            )
+            .await?;
+
+        data.node.map(|node| node.comments).ok_or_else(|| {
+            anyhow!(
+                "Failed to fetch review thread comments: thread {} not found",
+                thread_id
+            )
+        })
+    }
+
+    /// Resolve or unresolve a review thread by its GraphQL node ID.
+    /// Returns the thread's resolution state after the mutation.
+    pub async fn set_thread_resolved(&self, thread_id: &str, resolved: bool) -> Result<bool> {
+        let (mutation, field, action) = if resolved {
+            (
+                r#"
+                mutation($threadId: ID!) {
+                  resolveReviewThread(input: { threadId: $threadId }) {
+                    thread { isResolved }
+                  }
+                }
+                "#,
+                "resolveReviewThread",
+                "resolve thread",
+            )
+        } else {
+            (
+                r#"
+                mutation($threadId: ID!) {
+                  unresolveReviewThread(input: { threadId: $threadId }) {
+                    thread { isResolved }
+                  }
+                }
+                "#,
+                "unresolveReviewThread",
+                "unresolve thread",
+            )
+        };
+
+        let data: serde_json::Value = self
+            .graphql(
+                mutation,
+                serde_json::json!({ "threadId": thread_id }),
+                action,
+            )
+            .await?;
+
+        thread_resolution(&data, field, action)
+    }
+
+    /// Fetch the viewer's "Viewed" state for each file in the PR, keyed by path
+    pub async fn get_viewed_states(&self, pr_ref: &PrRef) -> Result<HashMap<String, ViewedState>> {
+        const QUERY: &str = r#"
+            query($owner: String!, $repo: String!, $number: Int!, $cursor: String) {
+              repository(owner: $owner, name: $repo) {
+                pullRequest(number: $number) {
+                  files(first: 100, after: $cursor) {
+                    pageInfo { hasNextPage endCursor }
+                    nodes { path viewerViewedState }
+                  }
+                }
+              }
+            }
+        "#;
+
+        #[derive(serde::Deserialize)]
+        struct Data {
+            repository: Option<RepositoryRaw>,
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct RepositoryRaw {
+            pull_request: Option<PullRequestRaw>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct PullRequestRaw {
+            files: Option<FileConnection>,
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct FileConnection {
+            page_info: PageInfoRaw,
+            nodes: Vec<FileRaw>,
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct FileRaw {
+            path: String,
+            viewer_viewed_state: String,
+        }
+
+        let mut states = HashMap::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let data: Data = self
+                .graphql(
+                    QUERY,
+                    serde_json::json!({
+                        "owner": pr_ref.owner,
+                        "repo": pr_ref.repo,
+                        "number": pr_ref.number,
+                        "cursor": cursor,
+                    }),
+                    "fetch viewed files",
+                )
+                .await?;
+
+            let pull_request = data
+                .repository
+                .and_then(|r| r.pull_request)
+                .ok_or_else(|| anyhow!("Failed to fetch viewed files: pull request not found"))?;
+            let Some(connection) = pull_request.files else {
+                break;
+            };
+
+            for file in connection.nodes {
+                if let Some(state) = ViewedState::from_graphql(&file.viewer_viewed_state) {
+                    states.insert(file.path, state);
+                }
+            }
+
+            if !connection.page_info.has_next_page {
+                break;
+            }
+            cursor = connection.page_info.end_cursor;
+        }
+
+        Ok(states)
+    }
+
+    /// Tick or untick the viewer's "Viewed" checkbox for a file
+    pub async fn set_file_viewed(&self, pr_ref: &PrRef, path: &str, viewed: bool) -> Result<()> {
+        let (mutation, action) = if viewed {
+            (
+                r#"
+                mutation($pullRequestId: ID!, $path: String!) {
+                  markFileAsViewed(input: { pullRequestId: $pullRequestId, path: $path }) {
+                    clientMutationId
+                  }
+                }
+                "#,
+                "mark file as viewed",
+            )
+        } else {
+            (
+                r#"
+                mutation($pullRequestId: ID!, $path: String!) {
+                  unmarkFileAsViewed(input: { pullRequestId: $pullRequestId, path: $path }) {
+                    clientMutationId
+                  }
+                }
+                "#,
+                "unmark file as viewed",
+            )
+        };
+
+        let pull_request_id = self.get_pr_node_id(pr_ref, action).await?;
+
+        let _: serde_json::Value = self
+            .graphql(
+                mutation,
+                serde_json::json!({ "pullRequestId": pull_request_id, "path": path }),
+                action,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Fetch the PR's mergeability, review decision and check state
+    pub async fn get_merge_status(&self, pr_ref: &PrRef) -> Result<MergeStatus> {
+        const QUERY: &str = r#"
+            query($owner: String!, $repo: String!, $number: Int!) {
+              repository(owner: $owner, name: $repo) {
+                pullRequest(number: $number) {
+                  id
+                  headRefOid
+                  state
+                  isDraft
+                  mergeable
+                  mergeStateStatus
+                  reviewDecision
+                  autoMergeRequest { enabledAt }
+                  commits(last: 1) { nodes { commit { statusCheckRollup { state } } } }
+                }
+              }
+            }
+        "#;
+
+        #[derive(serde::Deserialize)]
+        struct Data {
+            repository: Option<RepositoryRaw>,
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct RepositoryRaw {
+            pull_request: Option<PullRequestRaw>,
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct PullRequestRaw {
+            id: String,
+            head_ref_oid: String,
+            state: String,
+            is_draft: bool,
+            mergeable: String,
+            merge_state_status: String,
+            review_decision: Option<String>,
+            auto_merge_request: Option<serde_json::Value>,
+            commits: CommitConnection,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct CommitConnection {
+            nodes: Vec<CommitNodeRaw>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct CommitNodeRaw {
+            commit: CommitRaw,
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct CommitRaw {
+            status_check_rollup: Option<RollupRaw>,
+        }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_body = response.text().await.unwrap_or_default();
-            return Err(anyhow!(
-                "Failed to create comment: {} - {}",
-                status,
-                error_body
-            ));
+        #[derive(serde::Deserialize)]
+        struct RollupRaw {
+            state: String,
         }
 
-        let raw: CommentResponseRaw = response.json().await?;
+        let data: Data = self
+            .graphql(
+                QUERY,
+                serde_json::json!({
+                    "owner": pr_ref.owner,
+                    "repo": pr_ref.repo,
+                    "number": pr_ref.number,
+                }),
+                "fetch merge status",
+            )
+            .await?;
 
-        Ok(ReviewComment {
-            id: raw.id,
-            path: raw.path.unwrap_or_default(),
-            line: raw.line,
-            start_line: raw.start_line,
-            side: raw.side.unwrap_or_default(),
-            start_side: raw.start_side,
-            body: raw.body.unwrap_or_default(),
-            author: raw.user.map(|u| u.login).unwrap_or_default(),
-            created_at: raw.created_at.unwrap_or_default(),
-            html_url: raw.html_url.unwrap_or_default(),
-            in_reply_to_id: None,
+        let pr = data
+            .repository
+            .and_then(|r| r.pull_request)
+            .ok_or_else(|| anyhow!("Failed to fetch merge status: pull request not found"))?;
+
+        Ok(MergeStatus {
+            node_id: pr.id,
+            head_sha: pr.head_ref_oid,
+            state: pr.state,
+            is_draft: pr.is_draft,
+            mergeable: pr.mergeable,
+            merge_state_status: pr.merge_state_status,
+            review_decision: pr.review_decision,
+            checks_state: pr
+                .commits
+                .nodes
+                .into_iter()
+                .next()
+                .and_then(|c| c.commit.status_check_rollup)
+                .map(|r| r.state),
+            auto_merge_enabled: pr.auto_merge_request.is_some(),
         })
     }
 
-    pub async fn reply_to_comment(
+    /// Merge the PR, failing if its head is no longer `expected_head_sha`.
+    /// Returns the SHA of the resulting merge commit.
+    pub async fn merge_pr(
         &self,
         pr_ref: &PrRef,
-        comment_id: u64,
-        body: &str,
-    ) -> Result<ReviewComment> {
+        method: MergeMethod,
+        expected_head_sha: &str,
+        title: Option<&str>,
+        body: Option<&str>,
+    ) -> Result<String> {
         let url = format!(
-            "https://api.github.com/repos/{}/{}/pulls/{}/comments/{}/replies",
-            pr_ref.owner, pr_ref.repo, pr_ref.number, comment_id
+            "{}/repos/{}/{}/pulls/{}/merge",
+            self.api_base, pr_ref.owner, pr_ref.repo, pr_ref.number
         );
 
         #[derive(serde::Serialize)]
-        struct ReplyRequest {
-            body: String,
+        struct MergeRequest<'a> {
+            merge_method: &'a str,
+            sha: &'a str,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            commit_title: Option<&'a str>,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            commit_message: Option<&'a str>,
         }
 
         #[derive(serde::Deserialize)]
-        struct ReplyResponseRaw {
-            id: u64,
-            path: Option<String>,
-            line: Option<u32>,
-            side: Option<String>,
-            body: Option<String>,
-            user: Option<UserRaw>,
-            created_at: Option<String>,
-            html_url: Option<String>,
-            in_reply_to_id: Option<u64>,
+        struct MergeResponseRaw {
+            sha: Option<String>,
         }
 
-        #[derive(serde::Deserialize)]
-        struct UserRaw {
-            login: String,
-        }
+        let response = self
+            .http
+            .send_json(
+                Method::PUT,
+                &url,
+                &MergeRequest {
+                    merge_method: method.as_str(),
+                    sha: expected_head_sha,
+                    commit_title: title,
+                    commit_message: body,
+                },
+                "merge pull request",
+            )
+            .await?;
 
-        let client = reqwest::Client::new();
-        let response = client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.token))
-            .header("Accept", "application/vnd.github+json")
-            .header("User-Agent", "neo-reviewer")
-            .header("X-GitHub-Api-Version", "2022-11-28")
-            .json(&ReplyRequest {
-                body: body.to_string(),
-            })
-            .send()
+        let raw: MergeResponseRaw = response.json()?;
+        Ok(raw.sha.unwrap_or_default())
+    }
+
+    /// Turn on auto-merge, guarded by the expected head commit
+    pub async fn enable_auto_merge(
+        &self,
+        pull_request_id: &str,
+        method: MergeMethod,
+        expected_head_sha: &str,
+        title: Option<&str>,
+        body: Option<&str>,
+    ) -> Result<()> {
+        const MUTATION: &str = r#"
+            mutation(
+              $pullRequestId: ID!,
+              $mergeMethod: PullRequestMergeMethod!,
+              $expectedHeadOid: GitObjectID,
+              $commitHeadline: String,
+              $commitBody: String
+            ) {
+              enablePullRequestAutoMerge(input: {
+                pullRequestId: $pullRequestId,
+                mergeMethod: $mergeMethod,
+                expectedHeadOid: $expectedHeadOid,
+                commitHeadline: $commitHeadline,
+                commitBody: $commitBody
+              }) {
+                clientMutationId
+              }
+            }
+        "#;
+
+        let _: serde_json::Value = self
+            .graphql(
+                MUTATION,
+                serde_json::json!({
+                    "pullRequestId": pull_request_id,
+                    "mergeMethod": method.graphql_name(),
+                    "expectedHeadOid": expected_head_sha,
+                    "commitHeadline": title,
+                    "commitBody": body,
+                }),
+                "enable auto-merge",
+            )
             .await?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_body = response.text().await.unwrap_or_default();
-            return Err(anyhow!(
-                "Failed to reply to comment: {} - {}",
-                status,
-                error_body
-            ));
-        }
+        Ok(())
+    }
 
-        let raw: ReplyResponseRaw = response.json().await?;
+    pub async fn disable_auto_merge(&self, pull_request_id: &str) -> Result<()> {
+        const MUTATION: &str = r#"
+            mutation($pullRequestId: ID!) {
+              disablePullRequestAutoMerge(input: { pullRequestId: $pullRequestId }) {
+                clientMutationId
+              }
+            }
+        "#;
+
+        let _: serde_json::Value = self
+            .graphql(
+                MUTATION,
+                serde_json::json!({ "pullRequestId": pull_request_id }),
+                "disable auto-merge",
+            )
+            .await?;
 
-        Ok(ReviewComment {
-            id: raw.id,
-            path: raw.path.unwrap_or_default(),
-            line: raw.line,
-            start_line: None,
-            side: raw.side.unwrap_or_default(),
-            start_side: None,
-            body: raw.body.unwrap_or_default(),
-            author: raw.user.map(|u| u.login).unwrap_or_default(),
-            created_at: raw.created_at.unwrap_or_default(),
-            html_url: raw.html_url.unwrap_or_default(),
-            in_reply_to_id: raw.in_reply_to_id,
-        })
+        Ok(())
     }
 
-    pub async fn edit_review_comment(
+    /// Search open pull requests (e.g. `is:pr is:open review-requested:@me`),
+    /// returning at most `limit` results
+    pub async fn search_pull_requests(
         &self,
-        pr_ref: &PrRef,
-        comment_id: u64,
-        body: &str,
-    ) -> Result<ReviewComment> {
-        let url = format!(
-            "https://api.github.com/repos/{}/{}/pulls/comments/{}",
-            pr_ref.owner, pr_ref.repo, comment_id
-        );
+        query: &str,
+        limit: u32,
+    ) -> Result<Vec<SearchedPullRequest>> {
+        const QUERY: &str = r#"
+            query($query: String!, $limit: Int!) {
+              search(query: $query, type: ISSUE, first: $limit) {
+                nodes {
+                  ... on PullRequest {
+                    id
+                    number
+                    title
+                    url
+                    createdAt
+                    isDraft
+                    additions
+                    deletions
+                    headRefOid
+                    repository { nameWithOwner }
+                    author { login }
+                    viewerLatestReview { commit { oid } }
+                    reviewThreads(first: 100) {
+                      pageInfo { hasNextPage endCursor }
+                      nodes { isResolved }
+                    }
+                    commits(last: 1) { nodes { commit { statusCheckRollup { state } } } }
+                  }
+                }
+              }
+            }
+        "#;
 
-        #[derive(serde::Serialize)]
-        struct EditRequest {
-            body: String,
+        #[derive(serde::Deserialize)]
+        struct Data {
+            search: SearchRaw,
         }
 
         #[derive(serde::Deserialize)]
-        struct EditResponseRaw {
-            id: u64,
-            path: Option<String>,
-            line: Option<u32>,
-            start_line: Option<u32>,
-            side: Option<String>,
-            start_side: Option<String>,
-            body: Option<String>,
-            user: Option<UserRaw>,
-            created_at: Option<String>,
-            html_url: Option<String>,
-            in_reply_to_id: Option<u64>,
+        struct SearchRaw {
+            nodes: Vec<Option<PullRequestRaw>>,
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct PullRequestRaw {
+            id: String,
+            number: u64,
+            title: String,
+            url: String,
+            created_at: String,
+            is_draft: bool,
+            additions: u32,
+            deletions: u32,
+            head_ref_oid: String,
+            repository: RepositoryRaw,
+            author: Option<UserRaw>,
+            viewer_latest_review: Option<ReviewRaw>,
+            review_threads: ThreadStatesRaw,
+            commits: Nodes<CommitNodeRaw>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct Nodes<T> {
+            nodes: Vec<T>,
+        }
+
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct RepositoryRaw {
+            name_with_owner: String,
         }
 
         #[derive(serde::Deserialize)]
@@ -367,164 +2088,630 @@ impl GitHubClient {
             login: String,
         }
 
-        let client = reqwest::Client::new();
-        let response = client
-            .patch(&url)
-            .header("Authorization", format!("Bearer {}", self.token))
-            .header("Accept", "application/vnd.github+json")
-            .header("User-Agent", "neo-reviewer")
-            .header("X-GitHub-Api-Version", "2022-11-28")
-            .json(&EditRequest {
-                body: body.to_string(),
-            })
-            .send()
-            .await?;
+        #[derive(serde::Deserialize)]
+        struct ReviewRaw {
+            commit: Option<CommitOidRaw>,
+        }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_body = response.text().await.unwrap_or_default();
-            return Err(anyhow!(
-                "Failed to edit comment: {} - {}",
-                status,
-                error_body
-            ));
+        #[derive(serde::Deserialize)]
+        struct CommitOidRaw {
+            oid: String,
         }
 
-        let raw: EditResponseRaw = response.json().await?;
+        #[derive(serde::Deserialize)]
+        struct CommitNodeRaw {
+            commit: CommitRaw,
+        }
 
-        Ok(ReviewComment {
-            id: raw.id,
-            path: raw.path.unwrap_or_default(),
-            line: raw.line,
-            start_line: raw.start_line,
-            side: raw.side.unwrap_or_default(),
-            start_side: raw.start_side,
-            body: raw.body.unwrap_or_default(),
-            author: raw.user.map(|u| u.login).unwrap_or_default(),
-            created_at: raw.created_at.unwrap_or_default(),
-            html_url: raw.html_url.unwrap_or_default(),
-            in_reply_to_id: raw.in_reply_to_id,
-        })
-    }
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct CommitRaw {
+            status_check_rollup: Option<RollupRaw>,
+        }
 
-    pub async fn delete_review_comment(&self, pr_ref: &PrRef, comment_id: u64) -> Result<()> {
-        let url = format!(
-            "https://api.github.com/repos/{}/{}/pulls/comments/{}",
-            pr_ref.owner, pr_ref.repo, comment_id
-        );
+        #[derive(serde::Deserialize)]
+        struct RollupRaw {
+            state: String,
+        }
 
-        let client = reqwest::Client::new();
-        let response = client
-            .delete(&url)
-            .header("Authorization", format!("Bearer {}", self.token))
-            .header("Accept", "application/vnd.github+json")
-            .header("User-Agent", "neo-reviewer")
-            .header("X-GitHub-Api-Version", "2022-11-28")
-            .send()
+        let data: Data = self
+            .graphql(
+                QUERY,
+                serde_json::json!({ "query": query, "limit": limit.clamp(1, 100) }),
+                "search pull requests",
+            )
             .await?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_body = response.text().await.unwrap_or_default();
-            return Err(anyhow!(
-                "Failed to delete comment: {} - {}",
-                status,
-                error_body
-            ));
+        let mut prs = Vec::new();
+        for pr in data.search.nodes.into_iter().flatten() {
+            // PRs with more than a page of threads need the rest counted too.
+            let mut unresolved_threads = pr.review_threads.unresolved();
+            let mut page_info = pr.review_threads.page_info;
+            while page_info.has_next_page {
+                let page = self
+                    .get_thread_states(&pr.id, page_info.end_cursor.take())
+                    .await?;
+                unresolved_threads += page.unresolved();
+                page_info = page.page_info;
+            }
+
+            prs.push(SearchedPullRequest {
+                repo: pr.repository.name_with_owner,
+                number: pr.number,
+                title: pr.title,
+                url: pr.url,
+                author: pr.author.map(|u| u.login).unwrap_or_default(),
+                created_at: pr.created_at,
+                is_draft: pr.is_draft,
+                additions: pr.additions,
+                deletions: pr.deletions,
+                head_sha: pr.head_ref_oid,
+                ci_state: pr
+                    .commits
+                    .nodes
+                    .into_iter()
+                    .next()
+                    .and_then(|c| c.commit.status_check_rollup)
+                    .map(|r| r.state.to_lowercase()),
+                viewer_reviewed_sha: pr
+                    .viewer_latest_review
+                    .and_then(|r| r.commit)
+                    .map(|c| c.oid),
+                unresolved_threads,
+            });
         }
 
-        Ok(())
+        Ok(prs)
     }
 
-    pub async fn submit_review(
+    /// Fetch a page of a PR's review thread resolution states after `cursor`
+    async fn get_thread_states(
         &self,
-        pr_ref: &PrRef,
-        event: &str,
-        body: Option<&str>,
-    ) -> Result<()> {
-        let url = format!(
-            "https://api.github.com/repos/{}/{}/pulls/{}/reviews",
-            pr_ref.owner, pr_ref.repo, pr_ref.number
-        );
+        pull_request_id: &str,
+        cursor: Option<String>,
+    ) -> Result<ThreadStatesRaw> {
+        const QUERY: &str = r#"
+            query($pullRequestId: ID!, $cursor: String) {
+              node(id: $pullRequestId) {
+                ... on PullRequest {
+                  reviewThreads(first: 100, after: $cursor) {
+                    pageInfo { hasNextPage endCursor }
+                    nodes { isResolved }
+                  }
+                }
+              }
+            }
+        "#;
 
-        #[derive(serde::Serialize)]
-        struct ReviewRequest {
-            event: String,
-            #[serde(skip_serializing_if = "Option::is_none")]
-            body: Option<String>,
+        #[derive(serde::Deserialize)]
+        struct Data {
+            node: Option<PullRequestNodeRaw>,
         }
 
-        let client = reqwest::Client::new();
-        let response = client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.token))
-            .header("Accept", "application/vnd.github+json")
-            .header("User-Agent", "neo-reviewer")
-            .header("X-GitHub-Api-Version", "2022-11-28")
-            .json(&ReviewRequest {
-                event: event.to_string(),
-                body: body.map(|s| s.to_string()),
-            })
-            .send()
+        #[derive(serde::Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        struct PullRequestNodeRaw {
+            review_threads: ThreadStatesRaw,
+        }
+
+        let data: Data = self
+            .graphql(
+                QUERY,
+                serde_json::json!({ "pullRequestId": pull_request_id, "cursor": cursor }),
+                "count review threads",
+            )
             .await?;
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_body = response.text().await.unwrap_or_default();
+        data.node.map(|node| node.review_threads).ok_or_else(|| {
+            anyhow!(
+                "Failed to count review threads: pull request {} not found",
+                pull_request_id
+            )
+        })
+    }
 
-            #[derive(serde::Deserialize)]
-            struct GitHubError {
-                #[serde(default)]
-                errors: Vec<String>,
-            }
+    /// Run a GraphQL query, surfacing GraphQL-level errors as failures
+    async fn graphql<T: serde::de::DeserializeOwned>(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+        action: &str,
+    ) -> Result<T> {
+        #[derive(serde::Deserialize)]
+        struct GraphQlResponse<T> {
+            data: Option<T>,
+            #[serde(default)]
+            errors: Vec<GraphQlError>,
+        }
 
-            let friendly_msg = serde_json::from_str::<GitHubError>(&error_body)
-                .ok()
-                .and_then(|e| e.errors.into_iter().next())
-                .unwrap_or_else(|| format!("{} - {}", status, error_body));
+        #[derive(serde::Deserialize)]
+        struct GraphQlError {
+            message: String,
+        }
 
-            return Err(anyhow!("Failed to submit review: {}", friendly_msg));
+        let payload = serde_json::json!({
+            "query": query,
+            "variables": variables,
+        });
+        // Queries are read-only and can be retried like GETs; mutations cannot.
+        let response = if query.trim_start().starts_with("mutation") {
+            self.http
+                .send_json(Method::POST, &self.graphql_url, &payload, action)
+                .await?
+        } else {
+            self.http
+                .post_query(&self.graphql_url, &payload, action)
+                .await?
+        };
+
+        let raw: GraphQlResponse<T> = response.json()?;
+        if let Some(error) = raw.errors.into_iter().next() {
+            return Err(anyhow!("Failed to {}: {}", action, error.message));
         }
 
-        Ok(())
+        raw.data
+            .ok_or_else(|| anyhow!("Failed to {}: empty GraphQL response", action))
+    }
+}
+
+/// A review comment as returned by the REST API (list, create, reply, edit)
+#[derive(serde::Deserialize)]
+struct ReviewCommentRaw {
+    id: u64,
+    #[serde(default)]
+    node_id: String,
+    path: Option<String>,
+    line: Option<u32>,
+    start_line: Option<u32>,
+    side: Option<String>,
+    start_side: Option<String>,
+    body: Option<String>,
+    user: Option<CommentUserRaw>,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+    html_url: Option<String>,
+    in_reply_to_id: Option<u64>,
+    original_line: Option<u32>,
+    original_commit_id: Option<String>,
+    commit_id: Option<String>,
+    diff_hunk: Option<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct CommentUserRaw {
+    login: String,
+}
+
+/// Convert a REST review comment. Reactions and placement on the diff are
+/// filled in by the callers that need them.
+fn review_comment(raw: ReviewCommentRaw) -> ReviewComment {
+    ReviewComment {
+        id: raw.id,
+        path: raw.path.unwrap_or_default(),
+        line: raw.line,
+        start_line: raw.start_line,
+        side: raw.side.unwrap_or_default(),
+        start_side: raw.start_side,
+        body: raw.body.unwrap_or_default(),
+        author: raw.user.map(|u| u.login).unwrap_or_default(),
+        created_at: raw.created_at.unwrap_or_default(),
+        updated_at: raw.updated_at,
+        html_url: raw.html_url.unwrap_or_default(),
+        in_reply_to_id: raw.in_reply_to_id,
+        original_line: raw.original_line,
+        original_commit_id: raw.original_commit_id,
+        commit_id: raw.commit_id,
+        diff_hunk: raw.diff_hunk,
+        ..Default::default()
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PageInfoRaw {
+    has_next_page: bool,
+    end_cursor: Option<String>,
+}
+
+/// A GraphQL review thread, with every page of its comments once fetched
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReviewThreadRaw {
+    id: String,
+    path: String,
+    line: Option<u32>,
+    start_line: Option<u32>,
+    diff_side: Option<String>,
+    is_resolved: bool,
+    is_outdated: bool,
+    resolved_by: Option<CommentUserRaw>,
+    comments: ThreadCommentsRaw,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ThreadCommentsRaw {
+    page_info: PageInfoRaw,
+    nodes: Vec<ThreadCommentRaw>,
+}
+
+impl ThreadCommentsRaw {
+    /// Add the next page of comments, taking over its page info
+    fn append(&mut self, page: ThreadCommentsRaw) {
+        self.nodes.extend(page.nodes);
+        self.page_info = page.page_info;
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ThreadCommentRaw {
+    database_id: Option<u64>,
+    body: String,
+    url: String,
+    created_at: String,
+    author: Option<CommentUserRaw>,
+    reply_to: Option<ReplyToRaw>,
+    original_line: Option<u32>,
+    commit: Option<CommitOidRaw>,
+    original_commit: Option<CommitOidRaw>,
+    diff_hunk: Option<String>,
+    reaction_groups: Option<Vec<ReactionGroupRaw>>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReplyToRaw {
+    database_id: Option<u64>,
+}
+
+#[derive(serde::Deserialize)]
+struct CommitOidRaw {
+    oid: String,
+}
+
+/// One page of a PR's review threads, with only their resolution state
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ThreadStatesRaw {
+    page_info: PageInfoRaw,
+    nodes: Vec<ThreadStateRaw>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ThreadStateRaw {
+    is_resolved: bool,
+}
+
+impl ThreadStatesRaw {
+    fn unresolved(&self) -> u32 {
+        self.nodes.iter().filter(|t| !t.is_resolved).count() as u32
+    }
+}
+
+/// Convert a GraphQL review thread. Its comments take the thread's location,
+/// which is where GitHub shows the whole discussion.
+fn review_thread(raw: ReviewThreadRaw) -> ReviewThread {
+    let side = raw.diff_side.unwrap_or_default();
+    let comments = raw
+        .comments
+        .nodes
+        .into_iter()
+        .map(|c| ReviewComment {
+            id: c.database_id.unwrap_or_default(),
+            path: raw.path.clone(),
+            line: raw.line,
+            start_line: raw.start_line,
+            side: side.clone(),
+            body: c.body,
+            author: c.author.map(|u| u.login).unwrap_or_default(),
+            created_at: c.created_at,
+            html_url: c.url,
+            in_reply_to_id: c.reply_to.and_then(|r| r.database_id),
+            reactions: reaction_groups(c.reaction_groups.unwrap_or_default()),
+            original_line: c.original_line,
+            commit_id: c.commit.map(|c| c.oid),
+            original_commit_id: c.original_commit.map(|c| c.oid),
+            diff_hunk: c.diff_hunk,
+            ..Default::default()
+        })
+        .collect();
+
+    ReviewThread {
+        id: raw.id,
+        path: raw.path,
+        line: raw.line,
+        start_line: raw.start_line,
+        side,
+        is_resolved: raw.is_resolved,
+        is_outdated: raw.is_outdated,
+        resolved_by: raw.resolved_by.map(|u| u.login),
+        comments,
     }
 }
 
+/// Read `isResolved` from a resolve/unresolve mutation's `field` payload
+fn thread_resolution(data: &serde_json::Value, field: &str, action: &str) -> Result<bool> {
+    data[field]["thread"]["isResolved"]
+        .as_bool()
+        .ok_or_else(|| anyhow!("Failed to {}: thread not returned", action))
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReactionGroupRaw {
+    content: String,
+    viewer_has_reacted: bool,
+    reactors: ReactorsRaw,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReactorsRaw {
+    total_count: u32,
+}
+
+/// Convert GraphQL reaction groups, dropping empty and unknown ones
+fn reaction_groups(raw: Vec<ReactionGroupRaw>) -> Vec<ReactionGroup> {
+    raw.into_iter()
+        .filter(|group| group.reactors.total_count > 0)
+        .filter_map(|group| {
+            Some(ReactionGroup {
+                content: group.content.parse().ok()?,
+                count: group.reactors.total_count,
+                viewer_has_reacted: group.viewer_has_reacted,
+            })
+        })
+        .collect()
+}
+
+/// Seconds between two GitHub timestamps, if both are present
+fn duration_between(start: Option<&str>, end: Option<&str>) -> Option<u64> {
+    let start = parse_utc(start?)?;
+    let end = parse_utc(end?)?;
+    end.checked_sub(start)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    mod extract_next_page_url {
+    mod host_endpoints {
+        use super::*;
+
+        #[test]
+        fn github_dot_com_uses_public_api() {
+            assert_eq!(api_base_for_host("github.com"), "https://api.github.com");
+            assert_eq!(
+                graphql_url_for_host("github.com"),
+                "https://api.github.com/graphql"
+            );
+        }
+
+        #[test]
+        fn enterprise_host_uses_api_prefix() {
+            assert_eq!(
+                api_base_for_host("ghe.example.com"),
+                "https://ghe.example.com/api/v3"
+            );
+            assert_eq!(
+                graphql_url_for_host("ghe.example.com"),
+                "https://ghe.example.com/api/graphql"
+            );
+        }
+    }
+
+    mod url_encoding {
+        use super::*;
+
+        #[test]
+        fn branch_path_keeps_slashes_and_encodes_the_rest() {
+            assert_eq!(branch_path("feature/c++#2"), "feature/c%2B%2B%232");
+            assert_eq!(branch_path("fix me"), "fix%20me");
+        }
+
+        #[test]
+        fn query_value_encodes_plus_hash_and_ampersand() {
+            assert_eq!(query_value("me:c++#2&x"), "me%3Ac%2B%2B%232%26x");
+        }
+    }
+
+    mod reaction_groups {
+        use super::*;
+
+        #[test]
+        fn keeps_non_empty_groups() {
+            let raw: Vec<ReactionGroupRaw> = serde_json::from_value(serde_json::json!([
+                { "content": "THUMBS_UP", "viewerHasReacted": true, "reactors": { "totalCount": 2 } },
+                { "content": "HEART", "viewerHasReacted": false, "reactors": { "totalCount": 0 } },
+                { "content": "UNKNOWN", "viewerHasReacted": false, "reactors": { "totalCount": 1 } },
+            ]))
+            .unwrap();
+
+            assert_eq!(
+                reaction_groups(raw),
+                vec![ReactionGroup {
+                    content: ReactionContent::ThumbsUp,
+                    count: 2,
+                    viewer_has_reacted: true,
+                }]
+            );
+        }
+    }
+
+    mod review_threads {
         use super::*;
 
+        fn thread_raw(is_resolved: bool) -> ReviewThreadRaw {
+            serde_json::from_value(serde_json::json!({
+                "id": "PRRT_1",
+                "path": "src/lib.rs",
+                "line": 12,
+                "startLine": 10,
+                "diffSide": "RIGHT",
+                "isResolved": is_resolved,
+                "isOutdated": false,
+                "resolvedBy": if is_resolved { serde_json::json!({ "login": "author" }) } else { serde_json::Value::Null },
+                "comments": {
+                    "pageInfo": { "hasNextPage": false, "endCursor": null },
+                    "nodes": [
+                        {
+                            "databaseId": 1,
+                            "body": "Why?",
+                            "url": "https://github.com/owner/repo/pull/1#discussion_r1",
+                            "createdAt": "2026-01-01T00:00:00Z",
+                            "author": { "login": "reviewer" },
+                            "replyTo": null,
+                            "originalLine": 11,
+                            "commit": { "oid": "head" },
+                            "originalCommit": { "oid": "first" },
+                            "diffHunk": "@@ -1 +1 @@",
+                            "reactionGroups": [
+                                { "content": "EYES", "viewerHasReacted": false, "reactors": { "totalCount": 1 } }
+                            ]
+                        },
+                        {
+                            "databaseId": 2,
+                            "body": "Because",
+                            "url": "https://github.com/owner/repo/pull/1#discussion_r2",
+                            "createdAt": "2026-01-02T00:00:00Z",
+                            "author": null,
+                            "replyTo": { "databaseId": 1 },
+                            "originalLine": null,
+                            "commit": null,
+                            "originalCommit": null,
+                            "diffHunk": null,
+                            "reactionGroups": null
+                        }
+                    ]
+                }
+            }))
+            .unwrap()
+        }
+
+        #[test]
+        fn comments_take_the_thread_location() {
+            let thread = review_thread(thread_raw(false));
+
+            assert_eq!(thread.id, "PRRT_1");
+            assert_eq!(thread.side, "RIGHT");
+            assert!(!thread.is_resolved);
+            assert_eq!(thread.resolved_by, None);
+            assert_eq!(thread.comments.len(), 2);
+
+            let first = &thread.comments[0];
+            assert_eq!((first.id, first.path.as_str()), (1, "src/lib.rs"));
+            assert_eq!((first.line, first.start_line), (Some(12), Some(10)));
+            assert_eq!(first.side, "RIGHT");
+            assert_eq!(first.author, "reviewer");
+            assert_eq!(first.original_line, Some(11));
+            assert_eq!(first.commit_id.as_deref(), Some("head"));
+            assert_eq!(first.original_commit_id.as_deref(), Some("first"));
+            assert_eq!(first.reactions.len(), 1);
+
+            let reply = &thread.comments[1];
+            assert_eq!(reply.in_reply_to_id, Some(1));
+            assert_eq!(reply.line, Some(12));
+            assert_eq!(reply.author, "");
+            assert!(reply.reactions.is_empty());
+        }
+
+        #[test]
+        fn resolved_thread_reports_who_resolved_it() {
+            let thread = review_thread(thread_raw(true));
+
+            assert!(thread.is_resolved);
+            assert_eq!(thread.resolved_by.as_deref(), Some("author"));
+        }
+
         #[test]
-        fn finds_next_page_url() {
-            let link_header = concat!(
-                "<https://api.github.com/repos/o/r/pulls/1/comments?per_page=100&page=2>; rel=\"next\", ",
-                "<https://api.github.com/repos/o/r/pulls/1/comments?per_page=100&page=4>; rel=\"last\""
+        fn later_comment_pages_are_appended_in_order() {
+            let mut raw = thread_raw(false);
+            raw.comments.nodes.truncate(1);
+            raw.comments.page_info = PageInfoRaw {
+                has_next_page: true,
+                end_cursor: Some("c1".to_string()),
+            };
+            let page: ThreadCommentsRaw = serde_json::from_value(serde_json::json!({
+                "pageInfo": { "hasNextPage": false, "endCursor": "c2" },
+                "nodes": [{
+                    "databaseId": 101,
+                    "body": "Past the first page",
+                    "url": "",
+                    "createdAt": "2026-01-03T00:00:00Z",
+                    "author": { "login": "author" },
+                    "replyTo": { "databaseId": 1 },
+                    "originalLine": null,
+                    "commit": null,
+                    "originalCommit": null,
+                    "diffHunk": null,
+                    "reactionGroups": []
+                }]
+            }))
+            .unwrap();
+            raw.comments.append(page);
+            assert!(!raw.comments.page_info.has_next_page);
+
+            let ids: Vec<u64> = review_thread(raw).comments.iter().map(|c| c.id).collect();
+            assert_eq!(ids, vec![1, 101]);
+        }
+
+        #[test]
+        fn thread_resolution_reads_mutation_payload() {
+            let resolved =
+                serde_json::json!({ "resolveReviewThread": { "thread": { "isResolved": true } } });
+            assert!(thread_resolution(&resolved, "resolveReviewThread", "resolve thread").unwrap());
+
+            let unresolved = serde_json::json!({ "unresolveReviewThread": { "thread": { "isResolved": false } } });
+            assert!(
+                !thread_resolution(&unresolved, "unresolveReviewThread", "unresolve thread")
+                    .unwrap()
             );
+        }
 
-            let result = extract_next_page_url(link_header);
+        #[test]
+        fn thread_resolution_errors_without_thread() {
+            let data = serde_json::json!({ "resolveReviewThread": { "thread": null } });
+            let err =
+                thread_resolution(&data, "resolveReviewThread", "resolve thread").unwrap_err();
             assert_eq!(
-                result.as_deref(),
-                Some("https://api.github.com/repos/o/r/pulls/1/comments?per_page=100&page=2")
+                err.to_string(),
+                "Failed to resolve thread: thread not returned"
             );
         }
+    }
+
+    mod thread_states {
+        use super::*;
+
+        #[test]
+        fn counts_unresolved_threads_on_a_page() {
+            let page: ThreadStatesRaw = serde_json::from_value(serde_json::json!({
+                "pageInfo": { "hasNextPage": true, "endCursor": "c1" },
+                "nodes": [{ "isResolved": false }, { "isResolved": true }, { "isResolved": false }]
+            }))
+            .unwrap();
+
+            assert_eq!(page.unresolved(), 2);
+            assert!(page.page_info.has_next_page);
+        }
+    }
+
+    mod duration_between {
+        use super::*;
 
         #[test]
-        fn returns_none_without_next_relation() {
-            let link_header =
-                "<https://api.github.com/repos/o/r/pulls/1/comments?page=4>; rel=\"last\"";
-            let result = extract_next_page_url(link_header);
-            assert_eq!(result, None);
+        fn measures_completed_checks() {
+            assert_eq!(
+                duration_between(Some("2026-01-01T00:00:00Z"), Some("2026-01-01T00:02:05Z")),
+                Some(125)
+            );
         }
 
         #[test]
-        fn returns_none_for_malformed_next_segment() {
-            let link_header =
-                "https://api.github.com/repos/o/r/pulls/1/comments?page=2; rel=\"next\"";
-            let result = extract_next_page_url(link_header);
-            assert_eq!(result, None);
+        fn is_none_while_running() {
+            assert_eq!(duration_between(Some("2026-01-01T00:00:00Z"), None), None);
         }
     }
 
@@ -584,8 +2771,61 @@ mod tests {
         }
 
         #[test]
-        fn invalid_url_not_github() {
-            let result = GitHubClient::parse_pr_url("https://gitlab.com/owner/repo/pull/123");
+        fn valid_url_defaults_to_github_host() {
+            let pr_ref =
+                GitHubClient::parse_pr_url("https://github.com/owner/repo/pull/1").unwrap();
+            assert_eq!(pr_ref.host, "github.com");
+        }
+
+        #[test]
+        fn valid_url_strips_www_prefix() {
+            let pr_ref =
+                GitHubClient::parse_pr_url("https://www.github.com/owner/repo/pull/1").unwrap();
+            assert_eq!(pr_ref.host, "github.com");
+        }
+
+        #[test]
+        fn valid_enterprise_url() {
+            let pr_ref =
+                GitHubClient::parse_pr_url("https://GHE.Example.com/team/service/pull/77/files")
+                    .unwrap();
+            assert_eq!(pr_ref.host, "ghe.example.com");
+            assert_eq!(pr_ref.owner, "team");
+            assert_eq!(pr_ref.repo, "service");
+            assert_eq!(pr_ref.number, 77);
+        }
+
+        #[test]
+        fn valid_url_without_scheme() {
+            let pr_ref = GitHubClient::parse_pr_url("github.com/owner/repo/pull/123").unwrap();
+            assert_eq!(pr_ref.host, "github.com");
+            assert_eq!(pr_ref.owner, "owner");
+            assert_eq!(pr_ref.repo, "repo");
+            assert_eq!(pr_ref.number, 123);
+
+            let pr_ref = GitHubClient::parse_pr_url("ghe.example.com/team/service/pull/7").unwrap();
+            assert_eq!(pr_ref.host, "ghe.example.com");
+        }
+
+        #[test]
+        fn any_host_pull_url_is_treated_as_enterprise() {
+            // Only the path shape is checked: a non-GitHub host using
+            // GitHub's layout parses, and fails later at auth or API time.
+            let pr_ref =
+                GitHubClient::parse_pr_url("https://gitlab.com/owner/repo/pull/123").unwrap();
+            assert_eq!(pr_ref.host, "gitlab.com");
+        }
+
+        #[test]
+        fn invalid_url_gitlab_merge_request() {
+            let result =
+                GitHubClient::parse_pr_url("https://gitlab.com/owner/repo/-/merge_requests/123");
+            assert!(result.is_err());
+        }
+
+        #[test]
+        fn invalid_url_missing_host() {
+            let result = GitHubClient::parse_pr_url("owner/repo/pull/123");
             assert!(result.is_err());
         }
 
@@ -0,0 +1,793 @@
+use std::collections::BTreeMap;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Result, anyhow};
+use reqwest::header::{HeaderMap, LINK};
+use reqwest::{Method, StatusCode};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use super::cache::{CacheEntry, ResponseCache};
+use super::cassette::{
+    Interaction, Player, RECORDED_HEADERS, RecordedRequest, RecordedResponse, Recorder,
+};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// Longest we'll sleep for a rate limit to reset before giving up
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// HTTP settings for the current command, set once from CLI flags
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    pub timeout: Duration,
+    pub max_retries: u32,
+    /// REST API base overriding the one derived from the PR host
+    pub api_url: Option<String>,
+    /// GraphQL endpoint overriding the one derived from the PR host
+    pub graphql_url: Option<String>,
+    /// Directory to write request/response pairs to
+    pub record_dir: Option<PathBuf>,
+    /// Directory to serve recorded responses from instead of the network
+    pub replay_dir: Option<PathBuf>,
+    /// Revalidate GET responses against the on-disk ETag cache
+    pub cache: bool,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_TIMEOUT,
+            max_retries: DEFAULT_MAX_RETRIES,
+            api_url: None,
+            graphql_url: None,
+            record_dir: None,
+            replay_dir: None,
+            cache: true,
+        }
+    }
+}
+
+static CONFIG: OnceLock<HttpConfig> = OnceLock::new();
+
+/// Set the HTTP settings used by every transport created afterwards
+pub fn configure(config: HttpConfig) {
+    let _ = CONFIG.set(config);
+}
+
+/// The HTTP settings for the current command
+pub fn config() -> HttpConfig {
+    CONFIG.get().cloned().unwrap_or_default()
+}
+
+/// A fully read HTTP response, either live or served from a cassette
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        Ok(serde_json::from_str(&self.body)?)
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+
+    fn from_recorded(recorded: RecordedResponse) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &recorded.headers {
+            headers.insert(
+                reqwest::header::HeaderName::from_bytes(name.as_bytes())?,
+                value.parse()?,
+            );
+        }
+
+        Ok(Self {
+            status: StatusCode::from_u16(recorded.status)?,
+            headers,
+            body: recorded.body_text(),
+        })
+    }
+
+    fn to_recorded(&self) -> RecordedResponse {
+        let headers = RECORDED_HEADERS
+            .iter()
+            .filter_map(|name| Some((name.to_string(), self.header(name)?.to_string())))
+            .collect::<BTreeMap<_, _>>();
+        RecordedResponse::from_text(self.status.as_u16(), headers, &self.body)
+    }
+}
+
+enum Backend {
+    Live(reqwest::Client),
+    Replay(Player),
+}
+
+/// Rate limit state reported by GitHub response headers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RateLimit {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// Unix timestamp at which the quota resets
+    pub reset: Option<u64>,
+    /// Seconds to wait before retrying (secondary rate limits)
+    pub retry_after: Option<u64>,
+}
+
+impl RateLimit {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let number = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse().ok())
+        };
+
+        Self {
+            limit: number("x-ratelimit-limit"),
+            remaining: number("x-ratelimit-remaining"),
+            reset: number("x-ratelimit-reset"),
+            retry_after: number("retry-after"),
+        }
+    }
+
+    fn is_exhausted(&self) -> bool {
+        self.remaining == Some(0)
+    }
+
+    fn describe(&self) -> Option<String> {
+        let remaining = self.remaining?;
+        let quota = match self.limit {
+            Some(limit) => format!("{}/{}", remaining, limit),
+            None => remaining.to_string(),
+        };
+        Some(match self.reset {
+            Some(reset) => format!(
+                "rate limit: {} remaining, resets at {}",
+                quota,
+                format_utc(reset)
+            ),
+            None => format!("rate limit: {} remaining", quota),
+        })
+    }
+}
+
+/// A non-success response from the GitHub API
+#[derive(Debug, thiserror::Error)]
+#[error("Failed to {action}: {}", self.message())]
+pub struct ApiError {
+    pub action: String,
+    pub status: StatusCode,
+    pub body: String,
+    pub rate_limit: RateLimit,
+}
+
+impl ApiError {
+    fn message(&self) -> String {
+        let message = friendly_error(self.status, &self.body);
+        let rate_limited = self.rate_limit.is_exhausted()
+            || matches!(
+                self.status,
+                StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS
+            );
+
+        match self.rate_limit.describe() {
+            Some(quota) if rate_limited => format!("{} ({})", message, quota),
+            _ => message,
+        }
+    }
+}
+
+/// Shared HTTP transport for GitHub requests: one connection pool, common
+/// headers, retries with jittered backoff and rate-limit aware waiting.
+pub struct Transport {
+    backend: Backend,
+    recorder: Option<Recorder>,
+    cache: Option<ResponseCache>,
+    token: String,
+    max_retries: u32,
+}
+
+impl Transport {
+    pub fn new(token: String) -> Result<Self> {
+        let config = config();
+        let backend = match &config.replay_dir {
+            Some(dir) => Backend::Replay(Player::load(dir)?),
+            None => Backend::Live(
+                reqwest::Client::builder()
+                    .timeout(config.timeout)
+                    .user_agent("neo-reviewer")
+                    .build()?,
+            ),
+        };
+        let recorder = match &config.record_dir {
+            Some(dir) => Some(Recorder::new(dir)?),
+            None => None,
+        };
+        // Cassettes must capture and serve real exchanges, not cache revalidations.
+        let use_cache = config.cache && config.record_dir.is_none() && config.replay_dir.is_none();
+        let cache = if use_cache {
+            ResponseCache::open()
+        } else {
+            None
+        };
+
+        Ok(Self {
+            backend,
+            recorder,
+            cache,
+            token,
+            max_retries: config.max_retries,
+        })
+    }
+
+    /// Whether responses come from a cassette, in which case no token is needed
+    pub fn is_replaying() -> bool {
+        config().replay_dir.is_some()
+    }
+
+    pub async fn get(&self, url: &str, action: &str) -> Result<HttpResponse> {
+        self.execute(Method::GET, url, None, true, action).await
+    }
+
+    pub async fn get_json<T: DeserializeOwned>(&self, url: &str, action: &str) -> Result<T> {
+        self.get(url, action).await?.json()
+    }
+
+    /// Fetch every page of a list endpoint by following `Link: rel="next"`
+    pub async fn get_paginated<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        action: &str,
+    ) -> Result<Vec<T>> {
+        self.paginate(url, None, action).await
+    }
+
+    /// Like `get_paginated`, for endpoints that wrap each page's items in an
+    /// object, e.g. `{"total_count": 2, "check_runs": [...]}`
+    pub async fn get_paginated_field<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        field: &str,
+        action: &str,
+    ) -> Result<Vec<T>> {
+        self.paginate(url, Some(field), action).await
+    }
+
+    async fn paginate<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        field: Option<&str>,
+        action: &str,
+    ) -> Result<Vec<T>> {
+        let mut next_page_url = Some(url.to_string());
+        let mut items = Vec::new();
+
+        while let Some(page_url) = next_page_url {
+            let response = self.get(&page_url, action).await?;
+
+            next_page_url = response
+                .header(LINK.as_str())
+                .and_then(extract_next_page_url);
+
+            let mut page: Vec<T> = match field {
+                Some(field) => {
+                    let mut body: serde_json::Value = response.json()?;
+                    serde_json::from_value(body[field].take())?
+                }
+                None => response.json()?,
+            };
+            items.append(&mut page);
+        }
+
+        Ok(items)
+    }
+
+    /// Send a request with a JSON body; only idempotent methods are retried
+    /// after server errors
+    pub async fn send_json<B: Serialize + ?Sized>(
+        &self,
+        method: Method,
+        url: &str,
+        body: &B,
+        action: &str,
+    ) -> Result<HttpResponse> {
+        let idempotent = is_idempotent(&method);
+        let body = serde_json::to_value(body)?;
+        self.execute(method, url, Some(body), idempotent, action)
+            .await
+    }
+
+    /// POST a body whose effect is read-only (e.g. a GraphQL query), so it is
+    /// safe to retry like a GET
+    pub async fn post_query<B: Serialize + ?Sized>(
+        &self,
+        url: &str,
+        body: &B,
+        action: &str,
+    ) -> Result<HttpResponse> {
+        let body = serde_json::to_value(body)?;
+        self.execute(Method::POST, url, Some(body), true, action)
+            .await
+    }
+
+    pub async fn delete(&self, url: &str, action: &str) -> Result<HttpResponse> {
+        self.execute(Method::DELETE, url, None, true, action).await
+    }
+
+    async fn execute(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<serde_json::Value>,
+        idempotent: bool,
+        action: &str,
+    ) -> Result<HttpResponse> {
+        let cached = match &self.cache {
+            Some(cache) if method == Method::GET => cache.get(url),
+            _ => None,
+        };
+        let etag = cached.as_ref().map(|entry| entry.etag.as_str());
+        let mut attempt = 0;
+
+        loop {
+            match self.send_once(&method, url, body.as_ref(), etag).await {
+                Ok(response) => {
+                    let status = response.status;
+                    if status == StatusCode::NOT_MODIFIED
+                        && let Some(entry) = &cached
+                    {
+                        return HttpResponse::from_recorded(entry.response.clone());
+                    }
+
+                    if !status.is_success() {
+                        let rate_limit = RateLimit::from_headers(&response.headers);
+                        if attempt < self.max_retries
+                            && let Some(delay) =
+                                retry_delay(status, &rate_limit, idempotent, attempt, unix_now())
+                        {
+                            tokio::time::sleep(delay).await;
+                            attempt += 1;
+                            continue;
+                        }
+                    }
+
+                    self.record(&method, url, body.as_ref(), &response)?;
+
+                    if !status.is_success() {
+                        return Err(ApiError {
+                            action: action.to_string(),
+                            status,
+                            rate_limit: RateLimit::from_headers(&response.headers),
+                            body: response.body,
+                        }
+                        .into());
+                    }
+                    self.store(&method, url, &response);
+                    return Ok(response);
+                }
+                Err(e) => {
+                    // Connection failures never reached GitHub; timeouts may have,
+                    // so only idempotent requests are retried after them.
+                    let retryable = e
+                        .downcast_ref::<reqwest::Error>()
+                        .is_some_and(|e| e.is_connect() || (idempotent && e.is_timeout()));
+                    if retryable && attempt < self.max_retries {
+                        tokio::time::sleep(backoff(attempt, jitter())).await;
+                        attempt += 1;
+                        continue;
+                    }
+                    return Err(anyhow!("Failed to {}: {}", action, e));
+                }
+            }
+        }
+    }
+
+    async fn send_once(
+        &self,
+        method: &Method,
+        url: &str,
+        body: Option<&serde_json::Value>,
+        etag: Option<&str>,
+    ) -> Result<HttpResponse> {
+        let client = match &self.backend {
+            Backend::Replay(player) => {
+                return HttpResponse::from_recorded(player.play(method.as_str(), url, body)?);
+            }
+            Backend::Live(client) => client,
+        };
+
+        let mut request = client
+            .request(method.clone(), url)
+            .header("Authorization", format!("Bearer {}", self.token))
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", "2022-11-28");
+        if let Some(body) = body {
+            request = request.json(body);
+        }
+        if let Some(etag) = etag {
+            request = request.header("If-None-Match", etag);
+        }
+
+        let response = request.send().await?;
+        Ok(HttpResponse {
+            status: response.status(),
+            headers: response.headers().clone(),
+            body: response.text().await?,
+        })
+    }
+
+    fn store(&self, method: &Method, url: &str, response: &HttpResponse) {
+        let Some(cache) = &self.cache else {
+            return;
+        };
+        if *method != Method::GET {
+            return;
+        }
+
+        if let Some(etag) = response.header("etag") {
+            cache.put(&CacheEntry {
+                url: url.to_string(),
+                etag: etag.to_string(),
+                response: response.to_recorded(),
+            });
+        }
+    }
+
+    fn record(
+        &self,
+        method: &Method,
+        url: &str,
+        body: Option<&serde_json::Value>,
+        response: &HttpResponse,
+    ) -> Result<()> {
+        let Some(recorder) = &self.recorder else {
+            return Ok(());
+        };
+
+        recorder.record(&Interaction {
+            request: RecordedRequest {
+                method: method.to_string(),
+                url: url.to_string(),
+                body: body.cloned(),
+            },
+            response: response.to_recorded(),
+        })
+    }
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+    )
+}
+
+/// Decide whether (and how long) to wait before retrying a failed response.
+///
+/// Rate-limited requests are rejected before GitHub acts on them, so they are
+/// retried regardless of method; server errors only for idempotent requests.
+fn retry_delay(
+    status: StatusCode,
+    rate_limit: &RateLimit,
+    idempotent: bool,
+    attempt: u32,
+    now: u64,
+) -> Option<Duration> {
+    let rate_limited = matches!(
+        status,
+        StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS
+    ) && (rate_limit.retry_after.is_some() || rate_limit.is_exhausted());
+
+    if rate_limited {
+        let delay = match (rate_limit.retry_after, rate_limit.reset) {
+            (Some(seconds), _) => Duration::from_secs(seconds),
+            (None, Some(reset)) => Duration::from_secs(reset.saturating_sub(now) + 1),
+            (None, None) => backoff(attempt, jitter()),
+        };
+        return (delay <= MAX_RATE_LIMIT_WAIT).then_some(delay);
+    }
+
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return Some(backoff(attempt, jitter()));
+    }
+
+    let transient = matches!(
+        status,
+        StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    );
+    (idempotent && transient).then(|| backoff(attempt, jitter()))
+}
+
+/// Exponential backoff with up to 50% added jitter (`jitter` in `0.0..1.0`)
+fn backoff(attempt: u32, jitter: f64) -> Duration {
+    let exponential = BASE_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF);
+    exponential.mul_f64(1.0 + jitter.clamp(0.0, 1.0) / 2.0)
+}
+
+fn jitter() -> f64 {
+    let random = RandomState::new().hash_one(SystemTime::now());
+    (random % 1000) as f64 / 1000.0
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Parse a GitHub `YYYY-MM-DDTHH:MM:SSZ` timestamp into Unix seconds
+pub(crate) fn parse_utc(value: &str) -> Option<u64> {
+    let field = |range: std::ops::Range<usize>| -> Option<i64> { value.get(range)?.parse().ok() };
+    let (year, month, day) = (field(0..4)?, field(5..7)?, field(8..10)?);
+    let (hour, minute, second) = (field(11..13)?, field(14..16)?, field(17..19)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // Days-from-civil conversion, the inverse of `format_utc`
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let year_of_era = y - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146_097 + day_of_era - 719_468;
+
+    u64::try_from(days * 86_400 + hour * 3600 + minute * 60 + second).ok()
+}
+
+/// Format a Unix timestamp as an RFC 3339 UTC string
+fn format_utc(timestamp: u64) -> String {
+    let days = (timestamp / 86_400) as i64;
+    let seconds_of_day = timestamp % 86_400;
+
+    // Civil-from-days conversion (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        (seconds_of_day % 3600) / 60,
+        seconds_of_day % 60
+    )
+}
+
+fn extract_next_page_url(link_header: &str) -> Option<String> {
+    for segment in link_header.split(',') {
+        let part = segment.trim();
+        if !part.contains("rel=\"next\"") {
+            continue;
+        }
+
+        let start = part.find('<')?;
+        let end = part[start + 1..].find('>')? + start + 1;
+        return Some(part[start + 1..end].to_string());
+    }
+
+    None
+}
+
+/// Prefer the first entry of GitHub's `errors` array over the raw response body
+fn friendly_error(status: StatusCode, error_body: &str) -> String {
+    #[derive(serde::Deserialize)]
+    struct GitHubError {
+        #[serde(default)]
+        errors: Vec<String>,
+    }
+
+    serde_json::from_str::<GitHubError>(error_body)
+        .ok()
+        .and_then(|e| e.errors.into_iter().next())
+        .unwrap_or_else(|| format!("{} - {}", status, error_body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod extract_next_page_url {
+        use super::*;
+
+        #[test]
+        fn finds_next_page_url() {
+            let link_header = concat!(
+                "<https://api.github.com/repos/o/r/pulls/1/comments?per_page=100&page=2>; rel=\"next\", ",
+                "<https://api.github.com/repos/o/r/pulls/1/comments?per_page=100&page=4>; rel=\"last\""
+            );
+
+            let result = extract_next_page_url(link_header);
+            assert_eq!(
+                result.as_deref(),
+                Some("https://api.github.com/repos/o/r/pulls/1/comments?per_page=100&page=2")
+            );
+        }
+
+        #[test]
+        fn returns_none_without_next_relation() {
+            let link_header =
+                "<https://api.github.com/repos/o/r/pulls/1/comments?page=4>; rel=\"last\"";
+            let result = extract_next_page_url(link_header);
+            assert_eq!(result, None);
+        }
+
+        #[test]
+        fn returns_none_for_malformed_next_segment() {
+            let link_header =
+                "https://api.github.com/repos/o/r/pulls/1/comments?page=2; rel=\"next\"";
+            let result = extract_next_page_url(link_header);
+            assert_eq!(result, None);
+        }
+    }
+
+    mod friendly_error {
+        use super::*;
+
+        #[test]
+        fn prefers_first_github_error() {
+            let body = r#"{"message":"Unprocessable Entity","errors":["Can not approve your own pull request"]}"#;
+            let result = friendly_error(StatusCode::UNPROCESSABLE_ENTITY, body);
+            assert_eq!(result, "Can not approve your own pull request");
+        }
+
+        #[test]
+        fn falls_back_to_status_and_body() {
+            let result = friendly_error(StatusCode::BAD_GATEWAY, "oops");
+            assert_eq!(result, "502 Bad Gateway - oops");
+        }
+    }
+
+    mod retry_delay {
+        use super::*;
+
+        #[test]
+        fn honours_retry_after_on_secondary_rate_limit() {
+            let rate_limit = RateLimit {
+                retry_after: Some(5),
+                ..Default::default()
+            };
+            let delay = retry_delay(StatusCode::FORBIDDEN, &rate_limit, false, 0, 0);
+            assert_eq!(delay, Some(Duration::from_secs(5)));
+        }
+
+        #[test]
+        fn waits_for_reset_when_quota_is_exhausted() {
+            let rate_limit = RateLimit {
+                remaining: Some(0),
+                reset: Some(1_010),
+                ..Default::default()
+            };
+            let delay = retry_delay(StatusCode::FORBIDDEN, &rate_limit, true, 0, 1_000);
+            assert_eq!(delay, Some(Duration::from_secs(11)));
+        }
+
+        #[test]
+        fn gives_up_when_reset_is_too_far_away() {
+            let rate_limit = RateLimit {
+                remaining: Some(0),
+                reset: Some(10_000),
+                ..Default::default()
+            };
+            let delay = retry_delay(StatusCode::FORBIDDEN, &rate_limit, true, 0, 1_000);
+            assert_eq!(delay, None);
+        }
+
+        #[test]
+        fn does_not_retry_plain_forbidden() {
+            let rate_limit = RateLimit {
+                remaining: Some(4_000),
+                ..Default::default()
+            };
+            let delay = retry_delay(StatusCode::FORBIDDEN, &rate_limit, true, 0, 0);
+            assert_eq!(delay, None);
+        }
+
+        #[test]
+        fn retries_server_errors_only_when_idempotent() {
+            let rate_limit = RateLimit::default();
+            assert!(retry_delay(StatusCode::BAD_GATEWAY, &rate_limit, true, 0, 0).is_some());
+            assert!(retry_delay(StatusCode::BAD_GATEWAY, &rate_limit, false, 0, 0).is_none());
+            assert!(retry_delay(StatusCode::NOT_FOUND, &rate_limit, true, 0, 0).is_none());
+        }
+    }
+
+    mod backoff {
+        use super::*;
+
+        #[test]
+        fn grows_exponentially_and_caps() {
+            assert_eq!(backoff(0, 0.0), Duration::from_millis(500));
+            assert_eq!(backoff(2, 0.0), Duration::from_secs(2));
+            assert_eq!(backoff(20, 0.0), MAX_BACKOFF);
+        }
+
+        #[test]
+        fn adds_at_most_half_again_as_jitter() {
+            assert_eq!(backoff(1, 1.0), Duration::from_millis(1_500));
+        }
+    }
+
+    mod api_error {
+        use super::*;
+
+        #[test]
+        fn reports_quota_and_reset_when_rate_limited() {
+            let error = ApiError {
+                action: "fetch comments".to_string(),
+                status: StatusCode::FORBIDDEN,
+                body: "API rate limit exceeded".to_string(),
+                rate_limit: RateLimit {
+                    limit: Some(5_000),
+                    remaining: Some(0),
+                    reset: Some(1_700_000_000),
+                    retry_after: None,
+                },
+            };
+            assert_eq!(
+                error.to_string(),
+                "Failed to fetch comments: 403 Forbidden - API rate limit exceeded \
+(rate limit: 0/5000 remaining, resets at 2023-11-14T22:13:20Z)"
+            );
+        }
+
+        #[test]
+        fn omits_quota_for_unrelated_failures() {
+            let error = ApiError {
+                action: "fetch PR".to_string(),
+                status: StatusCode::NOT_FOUND,
+                body: "Not Found".to_string(),
+                rate_limit: RateLimit {
+                    remaining: Some(4_999),
+                    ..Default::default()
+                },
+            };
+            assert_eq!(
+                error.to_string(),
+                "Failed to fetch PR: 404 Not Found - Not Found"
+            );
+        }
+    }
+
+    #[test]
+    fn format_utc_handles_epoch_and_leap_years() {
+        assert_eq!(format_utc(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_utc(951_782_400), "2000-02-29T00:00:00Z");
+    }
+
+    #[test]
+    fn parse_utc_inverts_format_utc() {
+        assert_eq!(parse_utc("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_utc("2000-02-29T00:00:00Z"), Some(951_782_400));
+        for timestamp in [1_234_567_890, 1_767_225_599, 4_102_444_800] {
+            assert_eq!(parse_utc(&format_utc(timestamp)), Some(timestamp));
+        }
+        assert_eq!(parse_utc("not a date"), None);
+    }
+}
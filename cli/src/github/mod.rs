@@ -1,3 +1,6 @@
 pub mod auth;
+pub mod cache;
+pub mod cassette;
 pub mod client;
+pub mod http;
 pub mod types;
@@ -1,8 +1,13 @@
 use serde::{Deserialize, Serialize};
 
+/// Host of the public GitHub instance
+pub const DEFAULT_HOST: &str = "github.com";
+
 /// Parsed PR URL components
 #[derive(Debug, Clone)]
 pub struct PrRef {
+    /// `github.com` or a GitHub Enterprise Server hostname
+    pub host: String,
     pub owner: String,
     pub repo: String,
     pub number: u64,
@@ -11,8 +16,8 @@ pub struct PrRef {
 impl PrRef {
     pub fn url(&self) -> String {
         format!(
-            "https://github.com/{}/{}/pull/{}",
-            self.owner, self.repo, self.number
+            "https://{}/{}/{}/pull/{}",
+            self.host, self.owner, self.repo, self.number
         )
     }
 }
@@ -30,6 +35,28 @@ pub struct PullRequest {
     pub head_ref: String,
     pub author: String,
     pub state: String,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub draft: bool,
+    /// Milestone title
+    #[serde(default)]
+    pub milestone: Option<String>,
+    #[serde(default)]
+    pub review_comment_count: u32,
+}
+
+/// A commit in the PR
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrCommit {
+    pub sha: String,
+    pub message: String,
+    /// GitHub login when the author is linked to an account, else the git author name
+    pub author: String,
+    pub authored_at: Option<String>,
+    pub verified: bool,
+    /// GitHub's verification reason, e.g. `valid`, `unsigned` or `bad_email`
+    pub verification_reason: Option<String>,
 }
 
 /// A file changed in the PR
@@ -43,6 +70,50 @@ pub struct ReviewFile {
     pub content: Option<String>,
     /// Parsed change blocks from the diff
     pub change_blocks: Vec<crate::diff::types::ChangeBlock>,
+    /// CI annotations in this file that don't overlap any change block
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub annotations: Vec<Annotation>,
+    /// The viewer's "Viewed" checkbox state on GitHub (PR reviews only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub viewed_state: Option<ViewedState>,
+}
+
+/// State of GitHub's per-file "Viewed" checkbox for the viewer
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ViewedState {
+    Viewed,
+    Unviewed,
+    /// Marked viewed, but the file changed since
+    Dismissed,
+}
+
+impl ViewedState {
+    /// Parse GitHub's GraphQL `FileViewedState` value
+    pub fn from_graphql(value: &str) -> Option<Self> {
+        match value {
+            "VIEWED" => Some(ViewedState::Viewed),
+            "UNVIEWED" => Some(ViewedState::Unviewed),
+            "DISMISSED" => Some(ViewedState::Dismissed),
+            _ => None,
+        }
+    }
+}
+
+/// A CI check-run annotation (lint warning, test failure, ...) on a file range
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Annotation {
+    /// Name of the check run that published it
+    pub check_name: String,
+    pub path: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    /// notice, warning or failure
+    pub level: String,
+    pub title: Option<String>,
+    pub message: String,
+    /// True when the annotated lines aren't part of the PR diff
+    pub outside_diff: bool,
 }
 
 /// File change status
@@ -67,7 +138,7 @@ impl From<&str> for FileStatus {
 }
 
 /// A review comment on the PR
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ReviewComment {
     pub id: u64,
     pub path: String,
@@ -78,19 +149,331 @@ pub struct ReviewComment {
     pub body: String,
     pub author: String,
     pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
     pub html_url: String,
     pub in_reply_to_id: Option<u64>,
+    /// Reactions with a non-zero count
+    #[serde(default)]
+    pub reactions: Vec<ReactionGroup>,
+    /// Line the comment was left on, in `original_commit_id`
+    #[serde(default)]
+    pub original_line: Option<u32>,
+    #[serde(default)]
+    pub original_commit_id: Option<String>,
+    /// Commit `line` refers to; null `line` means the comment is outdated
+    #[serde(default)]
+    pub commit_id: Option<String>,
+    #[serde(default)]
+    pub diff_hunk: Option<String>,
+    /// Where an outdated comment's line ended up at the PR head
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relocation: Option<Relocation>,
+    /// Index into the file's `change_blocks` of the block the comment sits on
+    #[serde(default)]
+    pub change_block: Option<usize>,
+    #[serde(default)]
+    pub line_kind: Option<LineKind>,
+    /// Not on any change block of the PR diff; unset when the response
+    /// only covers one commit or an interdiff
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outside_diff: Option<bool>,
+}
+
+/// What kind of diff line a comment sits on
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LineKind {
+    Added,
+    /// Added line replacing a deletion
+    Changed,
+    Deleted,
+    Context,
+}
+
+/// How a line moved between two revisions
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RelocationStatus {
+    /// The line is unchanged, possibly shifted by edits around it
+    Moved,
+    /// The line was edited; `line` is the closest line of the replacement
+    Changed,
+    /// The line was deleted
+    Gone,
+}
+
+/// Result of mapping a line from one revision to another
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Relocation {
+    pub status: RelocationStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+}
+
+/// Reaction content, serialized with GitHub's REST names (`+1`, `heart`, ...)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReactionContent {
+    #[serde(rename = "+1")]
+    ThumbsUp,
+    #[serde(rename = "-1")]
+    ThumbsDown,
+    Laugh,
+    Hooray,
+    Confused,
+    Heart,
+    Rocket,
+    Eyes,
+}
+
+impl ReactionContent {
+    pub const ALL: [ReactionContent; 8] = [
+        ReactionContent::ThumbsUp,
+        ReactionContent::ThumbsDown,
+        ReactionContent::Laugh,
+        ReactionContent::Hooray,
+        ReactionContent::Confused,
+        ReactionContent::Heart,
+        ReactionContent::Rocket,
+        ReactionContent::Eyes,
+    ];
+
+    /// Name used by the REST API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReactionContent::ThumbsUp => "+1",
+            ReactionContent::ThumbsDown => "-1",
+            ReactionContent::Laugh => "laugh",
+            ReactionContent::Hooray => "hooray",
+            ReactionContent::Confused => "confused",
+            ReactionContent::Heart => "heart",
+            ReactionContent::Rocket => "rocket",
+            ReactionContent::Eyes => "eyes",
+        }
+    }
+
+    /// Name used by the GraphQL `ReactionContent` enum
+    pub fn graphql_name(&self) -> &'static str {
+        match self {
+            ReactionContent::ThumbsUp => "THUMBS_UP",
+            ReactionContent::ThumbsDown => "THUMBS_DOWN",
+            ReactionContent::Laugh => "LAUGH",
+            ReactionContent::Hooray => "HOORAY",
+            ReactionContent::Confused => "CONFUSED",
+            ReactionContent::Heart => "HEART",
+            ReactionContent::Rocket => "ROCKET",
+            ReactionContent::Eyes => "EYES",
+        }
+    }
+}
+
+impl std::fmt::Display for ReactionContent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ReactionContent {
+    type Err = String;
+
+    /// Accepts REST names (`+1`) as well as GraphQL names (`THUMBS_UP`)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let normalized = s.to_uppercase().replace('-', "_");
+        match normalized.as_str() {
+            "+1" | "THUMBS_UP" => return Ok(ReactionContent::ThumbsUp),
+            "_1" | "THUMBS_DOWN" => return Ok(ReactionContent::ThumbsDown),
+            _ => {}
+        }
+
+        ReactionContent::ALL
+            .into_iter()
+            .find(|content| content.graphql_name() == normalized)
+            .ok_or_else(|| {
+                format!(
+                    "Invalid reaction '{}': expected one of +1, -1, laugh, hooray, confused, heart, rocket, eyes",
+                    s
+                )
+            })
+    }
+}
+
+/// How many times a comment received a reaction, and whether the viewer gave it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ReactionGroup {
+    pub content: ReactionContent,
+    pub count: u32,
+    pub viewer_has_reacted: bool,
+}
+
+/// A review thread with its resolution state and nested comments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewThread {
+    /// GraphQL node ID, used to resolve/unresolve the thread
+    pub id: String,
+    pub path: String,
+    pub line: Option<u32>,
+    pub start_line: Option<u32>,
+    pub side: String,
+    pub is_resolved: bool,
+    pub is_outdated: bool,
+    pub resolved_by: Option<String>,
+    /// Comments in the thread, oldest first
+    pub comments: Vec<ReviewComment>,
+}
+
+/// Where a check result came from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckKind {
+    /// A GitHub Checks API run (Actions and most CI apps)
+    CheckRun,
+    /// A legacy commit status
+    Status,
+}
+
+/// A single CI result for the head commit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Check {
+    pub id: u64,
+    pub name: String,
+    pub kind: CheckKind,
+    /// success, failure, neutral, skipped, cancelled, timed_out, action_required
+    /// or error; None while the check is still running
+    pub conclusion: Option<String>,
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub duration_seconds: Option<u64>,
+    pub details_url: Option<String>,
+    /// Number of annotations published by a check run
+    pub annotations_count: u32,
+}
+
+/// Overall CI state for a commit
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckState {
+    Success,
+    Failure,
+    Pending,
+    /// No checks or statuses reported
+    None,
+}
+
+/// Check runs and statuses for a commit, with a rolled-up state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecksSummary {
+    pub state: CheckState,
+    pub passed: u32,
+    pub failed: u32,
+    pub pending: u32,
+    pub checks: Vec<Check>,
+}
+
+impl ChecksSummary {
+    pub fn from_checks(checks: Vec<Check>) -> Self {
+        let (mut passed, mut failed, mut pending) = (0, 0, 0);
+        for check in &checks {
+            match check.conclusion.as_deref() {
+                None => pending += 1,
+                Some("success" | "neutral" | "skipped") => passed += 1,
+                Some(_) => failed += 1,
+            }
+        }
+
+        let state = if failed > 0 {
+            CheckState::Failure
+        } else if pending > 0 {
+            CheckState::Pending
+        } else if passed > 0 {
+            CheckState::Success
+        } else {
+            CheckState::None
+        };
+
+        Self {
+            state,
+            passed,
+            failed,
+            pending,
+            checks,
+        }
+    }
+}
+
+/// Response from the checks command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecksResponse {
+    pub head_sha: String,
+    #[serde(flatten)]
+    pub summary: ChecksSummary,
+}
+
+/// A top-level comment on the PR conversation (an issue comment)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueComment {
+    pub id: u64,
+    pub body: String,
+    pub author: String,
+    pub created_at: String,
+    pub html_url: String,
+}
+
+/// A submitted review with its summary body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubmittedReview {
+    pub id: u64,
+    pub author: String,
+    /// APPROVED, CHANGES_REQUESTED, COMMENTED or DISMISSED
+    pub state: String,
+    pub submitted_at: Option<String>,
+    pub body: String,
+    pub html_url: String,
+    pub commit_id: Option<String>,
+}
+
+/// How a `fetch --since` interdiff was computed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interdiff {
+    /// Commit the diff starts from, e.g. the one last reviewed
+    pub since_sha: String,
+    /// The PR history was rewritten (force-pushed) after that commit
+    pub history_rewritten: bool,
+    /// The PR's merge base moved, e.g. after a rebase onto a newer base
+    pub base_changed: bool,
+    /// The reviewed changes didn't replay cleanly onto the new base, so
+    /// `files` is a direct diff and may include upstream changes
+    pub replay_conflicted: bool,
 }
 
 /// Response from the fetch command
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FetchResponse {
     pub pr: PullRequest,
+    pub commits: Vec<PrCommit>,
+    /// Set when `files` holds a single commit's changes instead of the whole PR
+    pub commit: Option<String>,
+    /// Set when `files` only holds changes since an earlier commit
+    pub interdiff: Option<Interdiff>,
     pub files: Vec<ReviewFile>,
     pub comments: Vec<ReviewComment>,
+    pub threads: Vec<ReviewThread>,
+    pub issue_comments: Vec<IssueComment>,
+    pub reviews: Vec<SubmittedReview>,
+    /// `None` when checks couldn't be read (e.g. the token lacks `checks:read`)
+    pub checks: Option<ChecksSummary>,
+    /// CI annotations on files that aren't part of the PR diff
+    pub unmatched_annotations: Vec<Annotation>,
     pub viewer: String,
 }
 
+/// Response from the conversation command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationResponse {
+    pub issue_comments: Vec<IssueComment>,
+    pub reviews: Vec<SubmittedReview>,
+}
+
 /// Response from the comment command
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommentResponse {
@@ -100,12 +483,370 @@ pub struct CommentResponse {
     pub error: Option<String>,
 }
 
+/// Event used when submitting a review
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReviewEvent {
+    Approve,
+    RequestChanges,
+    Comment,
+}
+
+impl ReviewEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReviewEvent::Approve => "APPROVE",
+            ReviewEvent::RequestChanges => "REQUEST_CHANGES",
+            ReviewEvent::Comment => "COMMENT",
+        }
+    }
+}
+
+impl std::fmt::Display for ReviewEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for ReviewEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_uppercase().replace('-', "_").as_str() {
+            "APPROVE" => Ok(ReviewEvent::Approve),
+            "REQUEST_CHANGES" => Ok(ReviewEvent::RequestChanges),
+            "COMMENT" => Ok(ReviewEvent::Comment),
+            _ => Err(format!(
+                "Invalid review event '{}': expected APPROVE, REQUEST_CHANGES or COMMENT",
+                s
+            )),
+        }
+    }
+}
+
+/// An inline comment submitted as part of a review
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DraftReviewComment {
+    pub path: String,
+    pub line: u32,
+    #[serde(default = "default_side")]
+    pub side: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_side: Option<String>,
+    pub body: String,
+}
+
+fn default_side() -> String {
+    "RIGHT".to_string()
+}
+
+/// A pending (not yet submitted) review owned by the viewer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingReview {
+    pub id: u64,
+    pub node_id: String,
+    pub commit_id: Option<String>,
+}
+
+/// Response from the review start/discard commands
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewResponse {
+    pub success: bool,
+    pub review_id: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Response from the threads command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadsResponse {
+    pub threads: Vec<ReviewThread>,
+}
+
+/// Response from the resolve-thread/unresolve-thread commands
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadResponse {
+    pub success: bool,
+    pub thread_id: Option<String>,
+    pub is_resolved: Option<bool>,
+    pub error: Option<String>,
+}
+
+/// An open pull request found by search, with the state needed to triage it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchedPullRequest {
+    /// `owner/name`
+    pub repo: String,
+    pub number: u64,
+    pub title: String,
+    pub url: String,
+    pub author: String,
+    pub created_at: String,
+    pub is_draft: bool,
+    pub additions: u32,
+    pub deletions: u32,
+    pub head_sha: String,
+    /// Combined check/status state of the head commit (success, failure, pending, ...)
+    pub ci_state: Option<String>,
+    /// Commit the viewer's latest review was left on, if they reviewed
+    pub viewer_reviewed_sha: Option<String>,
+    pub unresolved_threads: u32,
+}
+
+/// Why a pull request shows up in the inbox
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum InboxReason {
+    /// The viewer's review was requested
+    ReviewRequested,
+    /// The viewer reviewed it and new commits were pushed since
+    NewCommits,
+    /// The viewer's own PR has unresolved review threads
+    UnresolvedThreads,
+}
+
+/// A pull request that needs the viewer's attention
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboxItem {
+    pub reason: InboxReason,
+    /// Time since the PR was opened, e.g. `3h` or `2d`
+    pub age: String,
+    #[serde(flatten)]
+    pub pr: SearchedPullRequest,
+}
+
+/// Response from the inbox command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InboxResponse {
+    pub items: Vec<InboxItem>,
+}
+
+/// Whether a reviewer is a user or a team
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewerKind {
+    User,
+    Team,
+}
+
+/// A requested or completed reviewer
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Reviewer {
+    /// User login or team slug
+    pub name: String,
+    pub kind: ReviewerKind,
+    /// Latest review state for completed reviewers (APPROVED, CHANGES_REQUESTED, ...)
+    pub state: Option<String>,
+    pub submitted_at: Option<String>,
+}
+
+/// Response from the reviewers commands
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewersResponse {
+    pub success: bool,
+    /// Reviewers whose review is still requested
+    pub pending: Vec<Reviewer>,
+    /// Reviewers who have reviewed and aren't currently re-requested
+    pub completed: Vec<Reviewer>,
+    pub error: Option<String>,
+}
+
+/// How a pull request is merged
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeMethod {
+    Merge,
+    Squash,
+    Rebase,
+}
+
+impl MergeMethod {
+    /// Name used by the REST API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MergeMethod::Merge => "merge",
+            MergeMethod::Squash => "squash",
+            MergeMethod::Rebase => "rebase",
+        }
+    }
+
+    /// Name used by the GraphQL `PullRequestMergeMethod` enum
+    pub fn graphql_name(&self) -> &'static str {
+        match self {
+            MergeMethod::Merge => "MERGE",
+            MergeMethod::Squash => "SQUASH",
+            MergeMethod::Rebase => "REBASE",
+        }
+    }
+}
+
+impl std::fmt::Display for MergeMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for MergeMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "merge" => Ok(MergeMethod::Merge),
+            "squash" => Ok(MergeMethod::Squash),
+            "rebase" => Ok(MergeMethod::Rebase),
+            _ => Err(format!(
+                "Invalid merge method '{}': expected merge, squash or rebase",
+                s
+            )),
+        }
+    }
+}
+
+/// Mergeability of a PR as reported by GitHub's GraphQL API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeStatus {
+    /// GraphQL node ID, needed for auto-merge mutations
+    pub node_id: String,
+    pub head_sha: String,
+    /// OPEN, CLOSED or MERGED
+    pub state: String,
+    pub is_draft: bool,
+    /// MERGEABLE, CONFLICTING or UNKNOWN
+    pub mergeable: String,
+    /// CLEAN, BLOCKED, BEHIND, DIRTY, UNSTABLE, DRAFT, HAS_HOOKS or UNKNOWN
+    pub merge_state_status: String,
+    /// APPROVED, CHANGES_REQUESTED or REVIEW_REQUIRED (None without review rules)
+    pub review_decision: Option<String>,
+    /// Rolled-up check state of the head commit (SUCCESS, FAILURE, PENDING, ...)
+    pub checks_state: Option<String>,
+    pub auto_merge_enabled: bool,
+}
+
+/// A reason GitHub won't merge a PR
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeBlocker {
+    /// The head moved since the SHA the merge was guarded with
+    HeadChanged,
+    NotOpen,
+    Draft,
+    Conflicts,
+    BehindBase,
+    ReviewRequired,
+    ChangesRequested,
+    ChecksFailing,
+    ChecksPending,
+}
+
+/// Response from the merge command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeResponse {
+    pub success: bool,
+    pub merged: bool,
+    /// SHA of the merge (or squash/rebase) commit
+    pub merge_sha: Option<String>,
+    /// Auto-merge state after `--auto`/`--disable-auto`
+    pub auto_merge_enabled: Option<bool>,
+    /// Why the merge was refused, when GitHub blocked it
+    pub blockers: Vec<MergeBlocker>,
+    pub error: Option<String>,
+}
+
+/// Response from the mark-viewed/unmark-viewed commands
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewedResponse {
+    pub success: bool,
+    pub path: String,
+    pub viewed_state: Option<ViewedState>,
+    pub error: Option<String>,
+}
+
+/// A change reported by the watch command, printed as one NDJSON line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum WatchEvent {
+    /// Initial state once the first poll succeeded
+    Started {
+        head_sha: String,
+        /// `None` when checks couldn't be read
+        checks: Option<CheckState>,
+    },
+    CommentAdded {
+        comment: ReviewComment,
+    },
+    /// The comment's body or position changed
+    CommentEdited {
+        comment: ReviewComment,
+    },
+    CommentDeleted {
+        comment_id: u64,
+        path: String,
+    },
+    HeadChanged {
+        from: String,
+        to: String,
+    },
+    ReviewSubmitted {
+        review: SubmittedReview,
+    },
+    ChecksChanged {
+        head_sha: String,
+        state: CheckState,
+        passed: u32,
+        failed: u32,
+        pending: u32,
+    },
+    /// A poll failed; watching continues
+    Error {
+        message: String,
+    },
+}
+
+/// Response from the edit-pr command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditPrResponse {
+    pub success: bool,
+    /// The PR as it stands after the edit
+    pub pr: Option<PullRequest>,
+    pub error: Option<String>,
+}
+
+/// Response from the react/unreact commands
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReactionResponse {
+    pub success: bool,
+    pub comment_id: u64,
+    pub content: ReactionContent,
+    pub error: Option<String>,
+}
+
 /// Response from the comments command
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommentsResponse {
     pub comments: Vec<ReviewComment>,
 }
 
+/// A root review comment with its replies, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommentThread {
+    pub root: ReviewComment,
+    pub replies: Vec<ReviewComment>,
+    /// Comment authors in order of first appearance
+    pub participants: Vec<String>,
+    /// Creation time of the newest comment in the thread
+    pub last_activity: String,
+    /// Whether the newest comment is the viewer's
+    pub viewer_replied_last: bool,
+}
+
+/// Response from the comments command with `--threaded`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadedCommentsResponse {
+    pub threads: Vec<CommentThread>,
+    pub viewer: String,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -147,12 +888,153 @@ mod tests {
         }
     }
 
+    mod review_event_from_str {
+        use super::*;
+
+        #[test]
+        fn parses_all_events_case_insensitively() {
+            assert_eq!("APPROVE".parse(), Ok(ReviewEvent::Approve));
+            assert_eq!("approve".parse(), Ok(ReviewEvent::Approve));
+            assert_eq!("REQUEST_CHANGES".parse(), Ok(ReviewEvent::RequestChanges));
+            assert_eq!("request-changes".parse(), Ok(ReviewEvent::RequestChanges));
+            assert_eq!("COMMENT".parse(), Ok(ReviewEvent::Comment));
+        }
+
+        #[test]
+        fn rejects_unknown_event() {
+            assert!("PENDING".parse::<ReviewEvent>().is_err());
+            assert!("".parse::<ReviewEvent>().is_err());
+        }
+
+        #[test]
+        fn serializes_to_api_names() {
+            assert_eq!(
+                serde_json::to_string(&ReviewEvent::RequestChanges).unwrap(),
+                "\"REQUEST_CHANGES\""
+            );
+            assert_eq!(ReviewEvent::Comment.to_string(), "COMMENT");
+        }
+    }
+
+    mod viewed_state {
+        use super::*;
+
+        #[test]
+        fn parses_graphql_values() {
+            assert_eq!(
+                ViewedState::from_graphql("VIEWED"),
+                Some(ViewedState::Viewed)
+            );
+            assert_eq!(
+                ViewedState::from_graphql("DISMISSED"),
+                Some(ViewedState::Dismissed)
+            );
+            assert_eq!(ViewedState::from_graphql("unknown"), None);
+        }
+
+        #[test]
+        fn serializes_lowercase() {
+            assert_eq!(
+                serde_json::to_string(&ViewedState::Unviewed).unwrap(),
+                "\"unviewed\""
+            );
+        }
+    }
+
+    mod checks_summary {
+        use super::*;
+
+        fn check(conclusion: Option<&str>) -> Check {
+            Check {
+                id: 1,
+                name: "ci".to_string(),
+                kind: CheckKind::CheckRun,
+                conclusion: conclusion.map(str::to_string),
+                started_at: None,
+                completed_at: None,
+                duration_seconds: None,
+                details_url: None,
+                annotations_count: 0,
+            }
+        }
+
+        #[test]
+        fn failure_wins_over_pending() {
+            let summary = ChecksSummary::from_checks(vec![
+                check(Some("success")),
+                check(None),
+                check(Some("timed_out")),
+            ]);
+            assert_eq!(summary.state, CheckState::Failure);
+            assert_eq!((summary.passed, summary.failed, summary.pending), (1, 1, 1));
+        }
+
+        #[test]
+        fn pending_until_all_complete() {
+            let summary = ChecksSummary::from_checks(vec![check(Some("skipped")), check(None)]);
+            assert_eq!(summary.state, CheckState::Pending);
+        }
+
+        #[test]
+        fn success_when_all_pass() {
+            let summary =
+                ChecksSummary::from_checks(vec![check(Some("success")), check(Some("neutral"))]);
+            assert_eq!(summary.state, CheckState::Success);
+        }
+
+        #[test]
+        fn none_without_checks() {
+            assert_eq!(
+                ChecksSummary::from_checks(Vec::new()).state,
+                CheckState::None
+            );
+        }
+    }
+
+    mod reaction_content {
+        use super::*;
+
+        #[test]
+        fn parses_rest_and_graphql_names() {
+            assert_eq!(
+                "+1".parse::<ReactionContent>(),
+                Ok(ReactionContent::ThumbsUp)
+            );
+            assert_eq!(
+                "-1".parse::<ReactionContent>(),
+                Ok(ReactionContent::ThumbsDown)
+            );
+            assert_eq!(
+                "THUMBS_DOWN".parse::<ReactionContent>(),
+                Ok(ReactionContent::ThumbsDown)
+            );
+            assert_eq!(
+                "heart".parse::<ReactionContent>(),
+                Ok(ReactionContent::Heart)
+            );
+            assert!("clap".parse::<ReactionContent>().is_err());
+        }
+
+        #[test]
+        fn serializes_to_rest_names() {
+            assert_eq!(
+                serde_json::to_string(&ReactionContent::ThumbsUp).unwrap(),
+                "\"+1\""
+            );
+            assert_eq!(
+                serde_json::to_string(&ReactionContent::Rocket).unwrap(),
+                "\"rocket\""
+            );
+        }
+    }
+
     mod pr_ref_url {
         use super::*;
 
         #[test]
         fn formats_correctly() {
             let pr_ref = PrRef {
+                host: DEFAULT_HOST.to_string(),
                 owner: "octocat".to_string(),
                 repo: "hello-world".to_string(),
                 number: 42,
@@ -166,6 +1048,7 @@ mod tests {
         #[test]
         fn handles_special_chars_in_names() {
             let pr_ref = PrRef {
+                host: DEFAULT_HOST.to_string(),
                 owner: "my-org".to_string(),
                 repo: "my_repo.nvim".to_string(),
                 number: 1,
@@ -175,5 +1058,16 @@ mod tests {
                 "https://github.com/my-org/my_repo.nvim/pull/1"
             );
         }
+
+        #[test]
+        fn uses_enterprise_host() {
+            let pr_ref = PrRef {
+                host: "ghe.example.com".to_string(),
+                owner: "team".to_string(),
+                repo: "service".to_string(),
+                number: 7,
+            };
+            assert_eq!(pr_ref.url(), "https://ghe.example.com/team/service/pull/7");
+        }
     }
 }
@@ -2,14 +2,57 @@ mod commands;
 mod diff;
 mod github;
 
+use std::path::PathBuf;
+use std::time::Duration;
+
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 
+use crate::github::types::{DEFAULT_HOST, MergeMethod, ReactionContent, ReviewEvent};
+
 #[derive(Parser)]
 #[command(name = "neo-reviewer")]
 #[command(about = "CLI tool for reviewing GitHub pull requests in Neovim")]
 #[command(version)]
 struct Cli {
+    /// Timeout in seconds for each GitHub API request
+    #[arg(
+        long,
+        global = true,
+        env = "NEO_REVIEWER_TIMEOUT",
+        default_value_t = 30
+    )]
+    timeout: u64,
+
+    /// Maximum retries after transient failures and rate limits
+    #[arg(
+        long,
+        global = true,
+        env = "NEO_REVIEWER_MAX_RETRIES",
+        default_value_t = 3
+    )]
+    max_retries: u32,
+
+    /// Override the GitHub REST API base URL (e.g., a local stand-in server)
+    #[arg(long, global = true, env = "NEO_REVIEWER_API_URL")]
+    api_url: Option<String>,
+
+    /// Override the GitHub GraphQL URL (defaults to <api-url>/graphql when --api-url is set)
+    #[arg(long, global = true, env = "NEO_REVIEWER_GRAPHQL_URL")]
+    graphql_url: Option<String>,
+
+    /// Record every GitHub request/response pair into this directory
+    #[arg(long, global = true, value_name = "DIR", conflicts_with = "replay")]
+    record: Option<PathBuf>,
+
+    /// Skip the on-disk ETag cache (.git/neo-reviewer/cache) for this command
+    #[arg(long, global = true, env = "NEO_REVIEWER_NO_CACHE")]
+    no_cache: bool,
+
+    /// Serve GitHub responses from a directory recorded with --record
+    #[arg(long, global = true, value_name = "DIR", env = "NEO_REVIEWER_REPLAY")]
+    replay: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -40,13 +83,32 @@ enum Commands {
 
     /// Fetch PR data including files, change blocks, and content
     Fetch {
-        /// GitHub PR URL (e.g., https://github.com/owner/repo/pull/123)
+        /// GitHub PR URL (e.g., https://github.com/owner/repo/pull/123 or a GHES equivalent)
         #[arg(short, long)]
         url: String,
 
         /// Skip fetching review comments (diff/metadata only)
         #[arg(long)]
         skip_comments: bool,
+
+        /// Review a single PR commit (full or abbreviated SHA) against its parent
+        #[arg(long, conflicts_with_all = ["since", "since_last_review"])]
+        commit: Option<String>,
+
+        /// Only show changes made since this commit; rebases onto a newer base are ignored
+        #[arg(long, conflicts_with = "since_last_review")]
+        since: Option<String>,
+
+        /// Only show changes made since your latest submitted review
+        #[arg(long)]
+        since_last_review: bool,
+    },
+
+    /// Show CI check runs and commit statuses for the PR head
+    Checks {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
     },
 
     /// Add a review comment to a PR
@@ -55,13 +117,17 @@ enum Commands {
         #[arg(short, long)]
         url: String,
 
+        /// Post a top-level conversation comment instead of a line comment
+        #[arg(long, conflicts_with_all = ["path", "line", "start_line", "start_side"])]
+        pr_level: bool,
+
         /// File path to comment on
-        #[arg(short, long)]
-        path: String,
+        #[arg(short, long, required_unless_present = "pr_level")]
+        path: Option<String>,
 
         /// Line number to comment on (end line for multi-line comments)
-        #[arg(short, long)]
-        line: u32,
+        #[arg(short, long, required_unless_present = "pr_level")]
+        line: Option<u32>,
 
         /// Side of the diff (LEFT or RIGHT)
         #[arg(short, long, default_value = "RIGHT")]
@@ -78,6 +144,38 @@ enum Commands {
         /// Start side for multi-line comments (LEFT or RIGHT)
         #[arg(long)]
         start_side: Option<String>,
+
+        /// Lines refer to this PR commit (from `fetch --commit`); the comment
+        /// is anchored to the PR head when those lines are unchanged there
+        #[arg(long, conflicts_with = "pr_level")]
+        commit: Option<String>,
+    },
+
+    /// Suggest a change to a range of new-file lines, validated against the diff
+    Suggest {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+
+        /// File path to suggest a change in
+        #[arg(short, long)]
+        path: String,
+
+        /// End line of the range to replace (new-file line number)
+        #[arg(short, long)]
+        line: u32,
+
+        /// Start line of the range to replace (defaults to --line)
+        #[arg(long)]
+        start_line: Option<u32>,
+
+        /// Replacement text for the range (empty to suggest deleting it)
+        #[arg(short, long, allow_hyphen_values = true)]
+        replacement: String,
+
+        /// Optional message shown above the suggestion
+        #[arg(short, long)]
+        message: Option<String>,
     },
 
     /// Fetch existing review comments for a PR
@@ -85,6 +183,17 @@ enum Commands {
         /// GitHub PR URL
         #[arg(short, long)]
         url: String,
+
+        /// Group replies under their root comment
+        #[arg(long)]
+        threaded: bool,
+    },
+
+    /// Fetch conversation comments and submitted review summaries for a PR
+    Conversation {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
     },
 
     /// Reply to an existing comment
@@ -102,6 +211,87 @@ enum Commands {
         body: String,
     },
 
+    /// React to a review comment
+    React {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+
+        /// ID of the comment to react to
+        #[arg(short, long)]
+        comment_id: u64,
+
+        /// Reaction: +1, -1, laugh, hooray, confused, heart, rocket or eyes
+        #[arg(short = 'r', long, allow_hyphen_values = true)]
+        content: ReactionContent,
+    },
+
+    /// Remove your reaction from a review comment
+    Unreact {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+
+        /// ID of the comment to remove the reaction from
+        #[arg(short, long)]
+        comment_id: u64,
+
+        /// Reaction: +1, -1, laugh, hooray, confused, heart, rocket or eyes
+        #[arg(short = 'r', long, allow_hyphen_values = true)]
+        content: ReactionContent,
+    },
+
+    /// Resolve a review thread
+    ResolveThread {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+
+        /// GraphQL node ID of the thread to resolve
+        #[arg(short, long)]
+        thread_id: String,
+    },
+
+    /// Unresolve a previously resolved review thread
+    UnresolveThread {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+
+        /// GraphQL node ID of the thread to unresolve
+        #[arg(short, long)]
+        thread_id: String,
+    },
+
+    /// Tick GitHub's "Viewed" checkbox for a file
+    MarkViewed {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+
+        /// Path of the file to mark as viewed
+        #[arg(short, long)]
+        path: String,
+    },
+
+    /// Untick GitHub's "Viewed" checkbox for a file
+    UnmarkViewed {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+
+        /// Path of the file to mark as not viewed
+        #[arg(short, long)]
+        path: String,
+    },
+
+    /// Fetch review threads with resolved/outdated state
+    Threads {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+    },
+
     /// Edit an existing review comment
     EditComment {
         /// GitHub PR URL
@@ -128,23 +318,295 @@ enum Commands {
         comment_id: u64,
     },
 
-    /// Submit a review (approve or request changes)
+    /// Submit a review (approve, request changes or comment)
     Submit {
         /// GitHub PR URL
         #[arg(short, long)]
         url: String,
 
-        /// Review event: APPROVE or REQUEST_CHANGES
+        /// Review event: APPROVE, REQUEST_CHANGES or COMMENT
         #[arg(short, long)]
-        event: String,
+        event: ReviewEvent,
 
         /// Optional review body/message
         #[arg(short, long)]
         body: Option<String>,
+
+        /// JSON list of inline comments to submit with the review, e.g.
+        /// [{"path":"src/lib.rs","line":10,"side":"RIGHT","start_line":8,"body":"..."}]
+        #[arg(long)]
+        comments: Option<String>,
+    },
+
+    /// Push the current branch and open a PR from it
+    CreatePr {
+        /// Branch to merge into (defaults to the repository's default branch)
+        #[arg(long)]
+        base: Option<String>,
+
+        /// PR title
+        #[arg(short, long)]
+        title: String,
+
+        /// PR description
+        #[arg(short, long)]
+        body: Option<String>,
+
+        /// Open the PR as a draft
+        #[arg(long)]
+        draft: bool,
+
+        /// Repository as [HOST/]OWNER/REPO (defaults to the `origin` remote)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Remote to push the branch to (defaults to the branch's push remote, then `origin`)
+        #[arg(long)]
+        remote: Option<String>,
+    },
+
+    /// Edit a PR's title, description, labels or draft state
+    EditPr {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+
+        /// New PR title
+        #[arg(
+            short,
+            long,
+            required_unless_present_any = ["body", "add_labels", "remove_labels", "draft", "ready"]
+        )]
+        title: Option<String>,
+
+        /// New PR description
+        #[arg(short, long)]
+        body: Option<String>,
+
+        /// Label to add (repeatable)
+        #[arg(long = "add-label")]
+        add_labels: Vec<String>,
+
+        /// Label to remove (repeatable)
+        #[arg(long = "remove-label")]
+        remove_labels: Vec<String>,
+
+        /// Convert the PR to a draft
+        #[arg(long, conflicts_with = "ready")]
+        draft: bool,
+
+        /// Mark a draft PR as ready for review
+        #[arg(long)]
+        ready: bool,
+    },
+
+    /// Merge a PR, or enable/disable auto-merge
+    Merge {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+
+        /// Merge method: merge, squash or rebase
+        #[arg(short, long, default_value = "merge")]
+        method: MergeMethod,
+
+        /// Custom merge commit title
+        #[arg(short, long)]
+        title: Option<String>,
+
+        /// Custom merge commit message
+        #[arg(short, long)]
+        body: Option<String>,
+
+        /// Head SHA you reviewed (at least 7 hex characters); the merge is
+        /// refused if the PR has moved on
+        #[arg(long, required_unless_present = "disable_auto")]
+        sha: Option<String>,
+
+        /// Enable auto-merge once requirements are met instead of merging now
+        #[arg(long, conflicts_with = "disable_auto")]
+        auto: bool,
+
+        /// Disable auto-merge
+        #[arg(long, conflicts_with_all = ["title", "body"])]
+        disable_auto: bool,
+    },
+
+    /// Manage a pending review whose comments are published together on submit
+    Review {
+        #[command(subcommand)]
+        command: ReviewCommands,
+    },
+
+    /// Answer JSON-RPC 2.0 requests on stdin (one per line) for a long-lived
+    /// editor session, keeping auth, PR metadata and diffs between requests
+    Serve,
+
+    /// Poll a PR and stream changes (comments, pushes, reviews, CI) as NDJSON
+    Watch {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+
+        /// Seconds between polls
+        #[arg(long, default_value_t = 30)]
+        interval: u64,
+    },
+
+    /// List open PRs that need your attention: review requests, PRs you
+    /// reviewed that have new commits, and your PRs with unresolved threads
+    Inbox {
+        /// GitHub host to search (e.g., a GitHub Enterprise Server hostname)
+        #[arg(long, default_value = DEFAULT_HOST)]
+        hostname: String,
+
+        /// Maximum number of PRs to search per category (up to 100)
+        #[arg(long, default_value_t = 30)]
+        limit: u32,
+    },
+
+    /// List, request, re-request or remove reviewers (users and teams)
+    Reviewers {
+        #[command(subcommand)]
+        command: ReviewersCommands,
+    },
+
+    /// Manage the on-disk GitHub response cache
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
     },
 
     /// Check authentication status
-    Auth,
+    Auth {
+        /// GitHub host to check (e.g., a GitHub Enterprise Server hostname)
+        #[arg(long, default_value = DEFAULT_HOST)]
+        hostname: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ReviewersCommands {
+    /// Show pending and completed reviewers
+    List {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+    },
+
+    /// Request review from users and/or teams
+    Add {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+
+        /// User login to request review from (repeatable)
+        #[arg(long = "user", required_unless_present = "teams")]
+        users: Vec<String>,
+
+        /// Team slug (or org/slug) to request review from (repeatable)
+        #[arg(long = "team")]
+        teams: Vec<String>,
+    },
+
+    /// Withdraw review requests from users and/or teams
+    Remove {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+
+        /// User login to remove (repeatable)
+        #[arg(long = "user", required_unless_present = "teams")]
+        users: Vec<String>,
+
+        /// Team slug (or org/slug) to remove (repeatable)
+        #[arg(long = "team")]
+        teams: Vec<String>,
+    },
+
+    /// Re-request review (defaults to everyone who has already reviewed)
+    ReRequest {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+
+        /// User login to re-request review from (repeatable)
+        #[arg(long = "user")]
+        users: Vec<String>,
+
+        /// Team slug (or org/slug) to re-request review from (repeatable)
+        #[arg(long = "team")]
+        teams: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheCommands {
+    /// Remove all cached responses for the current repository
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum ReviewCommands {
+    /// Start a pending review (resumes the existing one if present)
+    Start {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+    },
+
+    /// Queue a comment on the pending review without publishing it
+    AddComment {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+
+        /// File path to comment on
+        #[arg(short, long)]
+        path: String,
+
+        /// Line number to comment on (end line for multi-line comments)
+        #[arg(short, long)]
+        line: u32,
+
+        /// Side of the diff (LEFT or RIGHT)
+        #[arg(short, long, default_value = "RIGHT")]
+        side: String,
+
+        /// Comment body
+        #[arg(short, long)]
+        body: String,
+
+        /// Start line for multi-line comments
+        #[arg(long)]
+        start_line: Option<u32>,
+
+        /// Start side for multi-line comments (LEFT or RIGHT)
+        #[arg(long)]
+        start_side: Option<String>,
+    },
+
+    /// Discard the pending review and all of its queued comments
+    Discard {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+    },
+
+    /// Submit the pending review with all of its queued comments (same as `submit`)
+    Submit {
+        /// GitHub PR URL
+        #[arg(short, long)]
+        url: String,
+
+        /// Review event: APPROVE, REQUEST_CHANGES or COMMENT
+        #[arg(short, long)]
+        event: ReviewEvent,
+
+        /// Optional review body/message
+        #[arg(short, long)]
+        body: Option<String>,
+    },
 }
 
 #[tokio::main]
@@ -155,6 +617,16 @@ async fn main() -> Result<()> {
 
     let cli = Cli::parse();
 
+    github::http::configure(github::http::HttpConfig {
+        timeout: Duration::from_secs(cli.timeout),
+        max_retries: cli.max_retries,
+        api_url: cli.api_url,
+        graphql_url: cli.graphql_url,
+        record_dir: cli.record,
+        replay_dir: cli.replay,
+        cache: !cli.no_cache,
+    });
+
     match cli.command {
         Commands::Diff {
             target,
@@ -171,31 +643,84 @@ async fn main() -> Result<()> {
                 tracked_only,
             })?;
         }
-        Commands::Fetch { url, skip_comments } => {
-            commands::fetch::run(&url, skip_comments).await?;
+        Commands::Fetch {
+            url,
+            skip_comments,
+            commit,
+            since,
+            since_last_review,
+        } => {
+            commands::fetch::run(
+                &url,
+                commands::fetch::FetchOpts {
+                    skip_comments,
+                    commit,
+                    since,
+                    since_last_review,
+                },
+            )
+            .await?;
+        }
+        Commands::Checks { url } => {
+            commands::checks::run(&url).await?;
         }
         Commands::Comment {
             url,
-            path,
-            line,
+            pr_level: true,
+            body,
+            ..
+        } => {
+            commands::comment::run_pr_level(&url, &body).await?;
+        }
+        Commands::Comment {
+            url,
+            pr_level: false,
+            path: Some(path),
+            line: Some(line),
             side,
             body,
             start_line,
             start_side,
+            commit,
         } => {
             commands::comment::run(
+                &url,
+                commands::comment::LineComment {
+                    path,
+                    line,
+                    side,
+                    body,
+                    start_line,
+                    start_side,
+                    commit,
+                },
+            )
+            .await?;
+        }
+        Commands::Suggest {
+            url,
+            path,
+            line,
+            start_line,
+            replacement,
+            message,
+        } => {
+            commands::suggest::run(
                 &url,
                 &path,
-                line,
-                &side,
-                &body,
                 start_line,
-                start_side.as_deref(),
+                line,
+                &replacement,
+                message.as_deref(),
             )
             .await?;
         }
-        Commands::Comments { url } => {
-            commands::comments::run(&url).await?;
+        Commands::Comment { .. } => unreachable!("clap requires --path and --line"),
+        Commands::Comments { url, threaded } => {
+            commands::comments::run(&url, threaded).await?;
+        }
+        Commands::Conversation { url } => {
+            commands::conversation::run(&url).await?;
         }
         Commands::Reply {
             url,
@@ -204,6 +729,35 @@ async fn main() -> Result<()> {
         } => {
             commands::reply::run(&url, comment_id, &body).await?;
         }
+        Commands::React {
+            url,
+            comment_id,
+            content,
+        } => {
+            commands::react::run_set_reaction(&url, comment_id, content, true).await?;
+        }
+        Commands::Unreact {
+            url,
+            comment_id,
+            content,
+        } => {
+            commands::react::run_set_reaction(&url, comment_id, content, false).await?;
+        }
+        Commands::ResolveThread { url, thread_id } => {
+            commands::threads::run_set_resolved(&url, &thread_id, true).await?;
+        }
+        Commands::UnresolveThread { url, thread_id } => {
+            commands::threads::run_set_resolved(&url, &thread_id, false).await?;
+        }
+        Commands::MarkViewed { url, path } => {
+            commands::viewed::run_set_viewed(&url, &path, true).await?;
+        }
+        Commands::UnmarkViewed { url, path } => {
+            commands::viewed::run_set_viewed(&url, &path, false).await?;
+        }
+        Commands::Threads { url } => {
+            commands::threads::run(&url).await?;
+        }
         Commands::EditComment {
             url,
             comment_id,
@@ -214,11 +768,152 @@ async fn main() -> Result<()> {
         Commands::DeleteComment { url, comment_id } => {
             commands::comment::run_delete(&url, comment_id).await?;
         }
-        Commands::Submit { url, event, body } => {
-            commands::submit::run(&url, &event, body.as_deref()).await?;
+        Commands::Submit {
+            url,
+            event,
+            body,
+            comments,
+        } => {
+            commands::submit::run(&url, event, body.as_deref(), comments.as_deref()).await?;
+        }
+        Commands::CreatePr {
+            base,
+            title,
+            body,
+            draft,
+            repo,
+            remote,
+        } => {
+            commands::create_pr::run(commands::create_pr::CreatePrOpts {
+                base,
+                title,
+                body,
+                draft,
+                repo,
+                remote,
+            })
+            .await?;
+        }
+        Commands::EditPr {
+            url,
+            title,
+            body,
+            add_labels,
+            remove_labels,
+            draft,
+            ready,
+        } => {
+            let draft = match (draft, ready) {
+                (true, _) => Some(true),
+                (_, true) => Some(false),
+                _ => None,
+            };
+            commands::edit_pr::run(
+                &url,
+                commands::edit_pr::EditPrOpts {
+                    title,
+                    body,
+                    add_labels,
+                    remove_labels,
+                    draft,
+                },
+            )
+            .await?;
+        }
+        Commands::Merge {
+            url,
+            method,
+            title,
+            body,
+            sha,
+            auto,
+            disable_auto,
+        } => {
+            use commands::merge::AutoMerge;
+
+            let auto = match (auto, disable_auto) {
+                (true, _) => Some(AutoMerge::Enable),
+                (_, true) => Some(AutoMerge::Disable),
+                _ => None,
+            };
+            commands::merge::run(
+                &url,
+                commands::merge::MergeOpts {
+                    method,
+                    title,
+                    body,
+                    sha,
+                    auto,
+                },
+            )
+            .await?;
+        }
+        Commands::Review { command } => match command {
+            ReviewCommands::Start { url } => {
+                commands::review::run_start(&url).await?;
+            }
+            ReviewCommands::AddComment {
+                url,
+                path,
+                line,
+                side,
+                body,
+                start_line,
+                start_side,
+            } => {
+                commands::review::run_add_comment(
+                    &url,
+                    &path,
+                    line,
+                    &side,
+                    &body,
+                    start_line,
+                    start_side.as_deref(),
+                )
+                .await?;
+            }
+            ReviewCommands::Discard { url } => {
+                commands::review::run_discard(&url).await?;
+            }
+            ReviewCommands::Submit { url, event, body } => {
+                commands::submit::run(&url, event, body.as_deref(), None).await?;
+            }
+        },
+        Commands::Serve => {
+            commands::serve::run().await?;
+        }
+        Commands::Watch { url, interval } => {
+            commands::watch::run(&url, interval).await?;
+        }
+        Commands::Inbox { hostname, limit } => {
+            commands::inbox::run(&hostname, limit).await?;
+        }
+        Commands::Reviewers { command } => {
+            use commands::reviewers::ReviewersAction;
+
+            let (url, action, users, teams) = match command {
+                ReviewersCommands::List { url } => {
+                    (url, ReviewersAction::List, Vec::new(), Vec::new())
+                }
+                ReviewersCommands::Add { url, users, teams } => {
+                    (url, ReviewersAction::Add, users, teams)
+                }
+                ReviewersCommands::Remove { url, users, teams } => {
+                    (url, ReviewersAction::Remove, users, teams)
+                }
+                ReviewersCommands::ReRequest { url, users, teams } => {
+                    (url, ReviewersAction::ReRequest, users, teams)
+                }
+            };
+            commands::reviewers::run(&url, action, &users, &teams).await?;
         }
-        Commands::Auth => {
-            commands::auth::run().await?;
+        Commands::Cache { command } => match command {
+            CacheCommands::Clear => {
+                commands::cache::run_clear()?;
+            }
+        },
+        Commands::Auth { hostname } => {
+            commands::auth::run(&hostname).await?;
         }
     }
 
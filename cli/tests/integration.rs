@@ -4,6 +4,14 @@ fn cli_path() -> &'static str {
     env!("CARGO_BIN_EXE_neo-reviewer")
 }
 
+fn cassette_path(name: &str) -> String {
+    format!(
+        "{}/tests/fixtures/cassettes/{}",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    )
+}
+
 mod tls {
     use super::*;
 
@@ -74,3 +82,762 @@ mod auth {
         assert!(!stderr.contains("panicked"), "CLI panicked: {stderr}");
     }
 }
+
+mod replay {
+    use super::*;
+
+    fn stdout_json(output: &std::process::Output) -> serde_json::Value {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        serde_json::from_str(&stdout).unwrap_or_else(|e| {
+            panic!(
+                "Invalid JSON output ({e}): {stdout}\nstderr: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )
+        })
+    }
+
+    /// Scratch directory under the system temp dir, emptied on creation
+    fn scratch_dir(name: &str) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("neo-reviewer-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn git(dir: &std::path::Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .expect("Failed to run git");
+        assert!(
+            output.status.success(),
+            "git {args:?} failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    /// Git repository with a base commit and a head commit changing `src/lib.rs`.
+    /// Returns (base SHA, head SHA).
+    fn pr_repo(dir: &std::path::Path) -> (String, String) {
+        git(dir, &["init", "--quiet"]);
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(dir.join("src/lib.rs"), "fn a() {}\n").unwrap();
+        git(dir, &["add", "."]);
+        git(dir, &["commit", "--quiet", "-m", "base"]);
+        let base = git(dir, &["rev-parse", "HEAD"]);
+        std::fs::write(dir.join("src/lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        git(dir, &["commit", "--quiet", "-am", "head"]);
+        let head = git(dir, &["rev-parse", "HEAD"]);
+        (base, head)
+    }
+
+    /// A recorded request without a body and its JSON response
+    fn interaction(
+        method: &str,
+        url: &str,
+        status: u16,
+        body: serde_json::Value,
+    ) -> serde_json::Value {
+        serde_json::json!({
+            "request": { "method": method, "url": url },
+            "response": {
+                "status": status,
+                "headers": { "content-type": "application/json; charset=utf-8" },
+                "body": body,
+            },
+        })
+    }
+
+    /// Write interactions as a cassette directory, in playback order
+    fn write_cassette(dir: &std::path::Path, interactions: &[serde_json::Value]) {
+        std::fs::create_dir_all(dir).unwrap();
+        for (i, interaction) in interactions.iter().enumerate() {
+            let method = interaction["request"]["method"].as_str().unwrap();
+            std::fs::write(
+                dir.join(format!("{:04}-{}.json", i + 1, method.to_lowercase())),
+                serde_json::to_string_pretty(interaction).unwrap(),
+            )
+            .unwrap();
+        }
+    }
+
+    fn pr_json(base: &str, head: &str) -> serde_json::Value {
+        serde_json::json!({
+            "number": 1,
+            "title": "Add b",
+            "state": "open",
+            "user": { "login": "author" },
+            "base": { "sha": base, "ref": "main" },
+            "head": { "sha": head, "ref": "feature" },
+        })
+    }
+
+    fn commits_json(shas: &[&str]) -> serde_json::Value {
+        shas.iter()
+            .map(|sha| {
+                serde_json::json!({
+                    "sha": sha,
+                    "commit": { "message": "commit" },
+                    "author": { "login": "author" },
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fetch_survives_unreadable_checks() {
+        let dir = scratch_dir("fetch-checks");
+        let repo = dir.join("repo");
+        std::fs::create_dir_all(&repo).unwrap();
+        let (base, head) = pr_repo(&repo);
+
+        let api = "https://api.github.com/repos/owner/repo";
+        let cassette = dir.join("cassette");
+        write_cassette(
+            &cassette,
+            &[
+                interaction("GET", &format!("{api}/pulls/1"), 200, pr_json(&base, &head)),
+                interaction(
+                    "GET",
+                    &format!("{api}/pulls/1/commits?per_page=100"),
+                    200,
+                    commits_json(&[&head]),
+                ),
+                interaction(
+                    "GET",
+                    "https://api.github.com/user",
+                    200,
+                    serde_json::json!({ "login": "reviewer" }),
+                ),
+                interaction(
+                    "GET",
+                    &format!("{api}/commits/{head}/check-runs?per_page=100"),
+                    403,
+                    serde_json::json!({ "message": "Resource not accessible by integration" }),
+                ),
+            ],
+        );
+
+        let output = Command::new(cli_path())
+            .args([
+                "--replay",
+                cassette.to_str().unwrap(),
+                "fetch",
+                "--url",
+                "https://github.com/owner/repo/pull/1",
+                "--skip-comments",
+            ])
+            .current_dir(&repo)
+            .output()
+            .expect("Failed to execute CLI");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "stderr: {stderr}");
+        assert!(
+            stderr.contains("Warning: couldn't fetch checks"),
+            "stderr: {stderr}"
+        );
+        let response = stdout_json(&output);
+        assert_eq!(response["pr"]["number"], 1);
+        assert_eq!(response["files"][0]["path"], "src/lib.rs");
+        assert!(response["checks"].is_null());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn fetch_commit_reviews_a_root_commit_against_the_empty_tree() {
+        let dir = scratch_dir("fetch-root-commit");
+        let repo = dir.join("repo");
+        std::fs::create_dir_all(&repo).unwrap();
+        let (base, _) = pr_repo(&repo);
+        git(&repo, &["checkout", "--quiet", "--orphan", "rewrite"]);
+        std::fs::write(repo.join("src/lib.rs"), "fn c() {}\n").unwrap();
+        git(&repo, &["commit", "--quiet", "-am", "root"]);
+        let root = git(&repo, &["rev-parse", "HEAD"]);
+
+        let api = "https://api.github.com/repos/owner/repo";
+        let cassette = dir.join("cassette");
+        write_cassette(
+            &cassette,
+            &[
+                interaction("GET", &format!("{api}/pulls/1"), 200, pr_json(&base, &root)),
+                interaction(
+                    "GET",
+                    &format!("{api}/pulls/1/commits?per_page=100"),
+                    200,
+                    commits_json(&[&root]),
+                ),
+                interaction(
+                    "GET",
+                    "https://api.github.com/user",
+                    200,
+                    serde_json::json!({ "login": "reviewer" }),
+                ),
+            ],
+        );
+
+        let output = Command::new(cli_path())
+            .args([
+                "--replay",
+                cassette.to_str().unwrap(),
+                "fetch",
+                "--url",
+                "https://github.com/owner/repo/pull/1",
+                "--commit",
+                &root,
+                "--skip-comments",
+            ])
+            .current_dir(&repo)
+            .output()
+            .expect("Failed to execute CLI");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(output.status.success(), "stderr: {stderr}");
+        let response = stdout_json(&output);
+        assert_eq!(response["files"][0]["path"], "src/lib.rs");
+        assert_eq!(response["files"][0]["status"], "added");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn comment_runs_offline_from_cassette() {
+        let cli = cli_path();
+
+        let output = Command::new(cli)
+            .args([
+                "--replay",
+                &cassette_path("comment"),
+                "comment",
+                "--url",
+                "https://github.com/owner/repo/pull/1",
+                "--path",
+                "src/lib.rs",
+                "--line",
+                "10",
+                "--body",
+                "Looks good",
+            ])
+            .env_remove("GITHUB_TOKEN")
+            .output()
+            .expect("Failed to execute CLI");
+
+        assert!(output.status.success());
+        let response = stdout_json(&output);
+        assert_eq!(response["success"], true);
+        assert_eq!(response["comment_id"], 42);
+    }
+
+    #[test]
+    fn submit_runs_offline_from_cassette() {
+        let cli = cli_path();
+
+        let output = Command::new(cli)
+            .args([
+                "--replay",
+                &cassette_path("submit"),
+                "submit",
+                "--url",
+                "https://github.com/owner/repo/pull/1",
+                "--event",
+                "APPROVE",
+                "--body",
+                "LGTM",
+            ])
+            .output()
+            .expect("Failed to execute CLI");
+
+        assert!(output.status.success());
+        let response = stdout_json(&output);
+        assert_eq!(response["success"], true);
+        assert_eq!(response["event"], "APPROVE");
+    }
+
+    #[test]
+    fn conversation_lists_issue_comments_and_submitted_reviews() {
+        let cli = cli_path();
+
+        let output = Command::new(cli)
+            .args([
+                "--replay",
+                &cassette_path("conversation"),
+                "conversation",
+                "--url",
+                "https://github.com/owner/repo/pull/1",
+            ])
+            .output()
+            .expect("Failed to execute CLI");
+
+        assert!(output.status.success());
+        let response = stdout_json(&output);
+        assert_eq!(response["issue_comments"][0]["author"], "maintainer");
+        let reviews = response["reviews"].as_array().unwrap();
+        assert_eq!(reviews.len(), 1, "pending reviews should be skipped");
+        assert_eq!(reviews[0]["state"], "APPROVED");
+        assert_eq!(reviews[0]["body"], "LGTM with nits");
+    }
+
+    #[test]
+    fn pr_level_comment_posts_to_conversation() {
+        let cli = cli_path();
+
+        let output = Command::new(cli)
+            .args([
+                "--replay",
+                &cassette_path("conversation"),
+                "comment",
+                "--url",
+                "https://github.com/owner/repo/pull/1",
+                "--pr-level",
+                "--body",
+                "Thanks, updated!",
+            ])
+            .output()
+            .expect("Failed to execute CLI");
+
+        assert!(output.status.success());
+        let response = stdout_json(&output);
+        assert_eq!(response["success"], true);
+        assert_eq!(response["comment_id"], 8);
+    }
+
+    #[test]
+    fn react_adds_reaction_to_comment() {
+        let cli = cli_path();
+
+        let output = Command::new(cli)
+            .args([
+                "--replay",
+                &cassette_path("react"),
+                "react",
+                "--url",
+                "https://github.com/owner/repo/pull/1",
+                "--comment-id",
+                "42",
+                "--content",
+                "+1",
+            ])
+            .output()
+            .expect("Failed to execute CLI");
+
+        assert!(output.status.success());
+        let response = stdout_json(&output);
+        assert_eq!(response["success"], true);
+        assert_eq!(response["content"], "+1");
+    }
+
+    #[test]
+    fn checks_summarize_runs_and_statuses() {
+        let cli = cli_path();
+
+        let output = Command::new(cli)
+            .args([
+                "--replay",
+                &cassette_path("checks"),
+                "checks",
+                "--url",
+                "https://github.com/owner/repo/pull/1",
+            ])
+            .output()
+            .expect("Failed to execute CLI");
+
+        assert!(output.status.success());
+        let response = stdout_json(&output);
+        assert_eq!(response["state"], "failure");
+        assert_eq!(response["failed"], 1);
+        assert_eq!(response["pending"], 1);
+        assert_eq!(response["passed"], 1);
+        let checks = response["checks"].as_array().unwrap();
+        assert_eq!(checks[0]["duration_seconds"], 180);
+        assert_eq!(checks[2]["kind"], "status");
+        assert_eq!(checks[2]["details_url"], "https://ci.example.com/build/9");
+    }
+
+    #[test]
+    fn edit_pr_updates_title_and_labels() {
+        let cli = cli_path();
+
+        let output = Command::new(cli)
+            .args([
+                "--replay",
+                &cassette_path("edit_pr"),
+                "edit-pr",
+                "--url",
+                "https://github.com/owner/repo/pull/1",
+                "--title",
+                "Add feature flag",
+                "--add-label",
+                "enhancement",
+                "--remove-label",
+                "needs review",
+            ])
+            .output()
+            .expect("Failed to execute CLI");
+
+        assert!(output.status.success());
+        let response = stdout_json(&output);
+        assert_eq!(response["success"], true);
+        assert_eq!(response["pr"]["title"], "Add feature flag");
+        assert_eq!(response["pr"]["labels"], serde_json::json!(["enhancement"]));
+        assert_eq!(response["pr"]["draft"], true);
+        assert_eq!(response["pr"]["milestone"], "v1.0");
+    }
+
+    #[test]
+    fn serve_answers_json_rpc_requests_over_stdio() {
+        use std::io::Write;
+        use std::process::Stdio;
+
+        let cli = cli_path();
+
+        let mut child = Command::new(cli)
+            .args(["--replay", &cassette_path("serve"), "serve"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to execute CLI");
+
+        let requests = [
+            r#"{"jsonrpc":"2.0","id":1,"method":"comment","params":{"url":"https://github.com/owner/repo/pull/1","path":"src/lib.rs","line":10,"body":"Looks good"}}"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"comment","params":{"url":"https://github.com/owner/repo/pull/1","path":"src/lib.rs","line":10,"body":"Nit: rename"}}"#,
+            r#"{"jsonrpc":"2.0","id":3,"method":"rebase"}"#,
+        ];
+        let mut stdin = child.stdin.take().unwrap();
+        for request in requests {
+            writeln!(stdin, "{request}").unwrap();
+        }
+        drop(stdin);
+
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+
+        let responses: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(responses.len(), 3);
+        // The cassette holds one PR lookup: the second comment reuses its head.
+        assert_eq!(responses[0]["result"]["comment_id"], 42);
+        assert_eq!(responses[1]["result"]["success"], true);
+        assert_eq!(responses[1]["result"]["comment_id"], 43);
+        assert_eq!(responses[2]["id"], 3);
+        assert_eq!(responses[2]["error"]["code"], -32601);
+    }
+
+    #[test]
+    fn serve_anchors_comments_to_the_latest_fetched_head() {
+        use std::io::Write;
+        use std::process::Stdio;
+
+        let dir = scratch_dir("serve-anchor");
+        let repo = dir.join("repo");
+        std::fs::create_dir_all(&repo).unwrap();
+        let (base, first_head) = pr_repo(&repo);
+        std::fs::write(repo.join("src/lib.rs"), "fn a() {}\nfn b() {}\nfn c() {}\n").unwrap();
+        git(&repo, &["commit", "--quiet", "-am", "push"]);
+        let second_head = git(&repo, &["rev-parse", "HEAD"]);
+
+        let api = "https://api.github.com/repos/owner/repo";
+        let fetch = |head: &str, commits: &[&str]| {
+            vec![
+                interaction("GET", &format!("{api}/pulls/1"), 200, pr_json(&base, head)),
+                interaction(
+                    "GET",
+                    &format!("{api}/pulls/1/commits?per_page=100"),
+                    200,
+                    commits_json(commits),
+                ),
+                interaction(
+                    "GET",
+                    "https://api.github.com/user",
+                    200,
+                    serde_json::json!({ "login": "reviewer" }),
+                ),
+            ]
+        };
+        let comment = |id: u64, head: &str| {
+            let mut interaction = interaction(
+                "POST",
+                &format!("{api}/pulls/1/comments"),
+                201,
+                serde_json::json!({ "id": id, "html_url": "" }),
+            );
+            interaction["request"]["body"] = serde_json::json!({
+                "body": "Nit",
+                "commit_id": head,
+                "path": "src/lib.rs",
+                "line": 2,
+                "side": "RIGHT",
+            });
+            interaction
+        };
+        let mut interactions = fetch(&first_head, &[&first_head]);
+        interactions.push(comment(1, &first_head));
+        interactions.push(comment(2, &first_head));
+        interactions.extend(fetch(&second_head, &[&first_head, &second_head]));
+        interactions.push(comment(3, &second_head));
+        let cassette = dir.join("cassette");
+        write_cassette(&cassette, &interactions);
+
+        let mut child = Command::new(cli_path())
+            .args(["--replay", cassette.to_str().unwrap(), "serve"])
+            .current_dir(&repo)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to execute CLI");
+
+        let fetch = r#"{"jsonrpc":"2.0","id":0,"method":"fetch","params":{"url":"https://github.com/owner/repo/pull/1","skip_comments":true}}"#;
+        let comment = r#"{"jsonrpc":"2.0","id":0,"method":"comment","params":{"url":"https://github.com/owner/repo/pull/1","path":"src/lib.rs","line":2,"body":"Nit"}}"#;
+        // Comments reuse the fetched head until the next fetch moves it.
+        let mut stdin = child.stdin.take().unwrap();
+        for request in [fetch, comment, comment, fetch, comment] {
+            writeln!(stdin, "{request}").unwrap();
+        }
+        drop(stdin);
+
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        let responses: Vec<serde_json::Value> = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            responses.len(),
+            5,
+            "stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(
+            responses[0]["result"]["pr"]["head_sha"],
+            first_head.as_str()
+        );
+        assert_eq!(responses[1]["result"]["comment_id"], 1);
+        assert_eq!(responses[2]["result"]["comment_id"], 2);
+        assert_eq!(
+            responses[3]["result"]["pr"]["head_sha"],
+            second_head.as_str()
+        );
+        assert_eq!(responses[4]["result"]["comment_id"], 3);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    fn run_review(cassette: &str, args: &[&str]) -> serde_json::Value {
+        let output = Command::new(cli_path())
+            .args(["--replay", &cassette_path(cassette), "review"])
+            .args(args)
+            .args(["--url", "https://github.com/owner/repo/pull/1"])
+            .env_remove("GITHUB_TOKEN")
+            .output()
+            .expect("Failed to execute CLI");
+
+        assert!(
+            output.status.success(),
+            "stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        stdout_json(&output)
+    }
+
+    #[test]
+    fn pending_review_start_add_comment_submit() {
+        let started = run_review("review/start", &["start"]);
+        assert_eq!(started["success"], true);
+        assert_eq!(started["review_id"], 7);
+
+        let comment = run_review(
+            "review/add_comment",
+            &[
+                "add-comment",
+                "--path",
+                "src/lib.rs",
+                "--line",
+                "2",
+                "--body",
+                "Needs a test",
+            ],
+        );
+        assert_eq!(comment["success"], true, "{comment}");
+        assert_eq!(comment["comment_id"], 99);
+        assert_eq!(
+            comment["html_url"],
+            "https://github.com/owner/repo/pull/1#discussion_r99"
+        );
+
+        let submitted = run_review(
+            "review/submit",
+            &[
+                "submit",
+                "--event",
+                "REQUEST_CHANGES",
+                "--body",
+                "See inline comments",
+            ],
+        );
+        assert_eq!(submitted["success"], true);
+        assert_eq!(submitted["event"], "REQUEST_CHANGES");
+        assert_eq!(submitted["review_id"], 7);
+        assert_eq!(submitted["comments"], 0);
+    }
+
+    #[test]
+    fn pending_review_start_reports_pr_errors_as_json() {
+        let started = run_review("review/start_unknown_pr", &["start"]);
+        assert_eq!(started["success"], false);
+        assert!(
+            started["error"].as_str().unwrap().contains("404"),
+            "{started}"
+        );
+    }
+
+    #[test]
+    fn pending_review_discard() {
+        let discarded = run_review("review/discard", &["discard"]);
+        assert_eq!(discarded["success"], true, "{discarded}");
+        assert_eq!(discarded["review_id"], 7);
+    }
+
+    /// Feature branch whose `origin` names the `fork/repo` GitHub repository
+    /// but pushes to a local bare repository. Returns (repo, bare) paths.
+    fn fork_checkout(dir: &std::path::Path) -> (std::path::PathBuf, std::path::PathBuf) {
+        let repo = dir.join("repo");
+        let bare = dir.join("bare.git");
+        std::fs::create_dir_all(&repo).unwrap();
+        std::fs::create_dir_all(&bare).unwrap();
+        git(&bare, &["init", "--quiet", "--bare"]);
+        pr_repo(&repo);
+        git(&repo, &["checkout", "--quiet", "-b", "feature"]);
+        git(
+            &repo,
+            &[
+                "remote",
+                "add",
+                "origin",
+                "https://github.com/fork/repo.git",
+            ],
+        );
+        git(
+            &repo,
+            &[
+                "remote",
+                "set-url",
+                "--push",
+                "origin",
+                bare.to_str().unwrap(),
+            ],
+        );
+        (repo, bare)
+    }
+
+    fn create_pr(repo: &std::path::Path, cassette: &std::path::Path, base: &str) -> String {
+        let output = Command::new(cli_path())
+            .args(["--replay", cassette.to_str().unwrap(), "create-pr"])
+            .args(["--repo", "owner/repo", "--base", base, "--title", "Add b"])
+            .current_dir(repo)
+            .output()
+            .expect("Failed to execute CLI");
+
+        assert!(!output.status.success());
+        String::from_utf8_lossy(&output.stderr).to_string()
+    }
+
+    #[test]
+    fn create_pr_refuses_an_existing_pr_before_pushing() {
+        let dir = scratch_dir("create-pr-existing");
+        let (repo, bare) = fork_checkout(&dir);
+
+        let api = "https://api.github.com/repos/owner/repo";
+        let cassette = dir.join("cassette");
+        write_cassette(
+            &cassette,
+            &[
+                interaction(
+                    "GET",
+                    &format!("{api}/branches/main"),
+                    200,
+                    serde_json::json!({ "name": "main" }),
+                ),
+                interaction(
+                    "GET",
+                    &format!("{api}/pulls?head=fork%3Afeature&state=open&per_page=1"),
+                    200,
+                    serde_json::json!([{ "number": 3 }]),
+                ),
+            ],
+        );
+
+        let stderr = create_pr(&repo, &cassette, "main");
+        assert!(
+            stderr.contains(
+                "A PR from fork:feature is already open: https://github.com/owner/repo/pull/3"
+            ),
+            "stderr: {stderr}"
+        );
+        assert_eq!(git(&bare, &["for-each-ref"]), "");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn create_pr_refuses_a_missing_base_before_pushing() {
+        let dir = scratch_dir("create-pr-base");
+        let (repo, bare) = fork_checkout(&dir);
+
+        let cassette = dir.join("cassette");
+        write_cassette(
+            &cassette,
+            &[interaction(
+                "GET",
+                "https://api.github.com/repos/owner/repo/branches/mian",
+                404,
+                serde_json::json!({ "message": "Branch not found" }),
+            )],
+        );
+
+        let stderr = create_pr(&repo, &cassette, "mian");
+        assert!(
+            stderr.contains("Base branch 'mian' does not exist in owner/repo"),
+            "stderr: {stderr}"
+        );
+        assert_eq!(git(&bare, &["for-each-ref"]), "");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unrecorded_request_fails() {
+        let cli = cli_path();
+
+        let output = Command::new(cli)
+            .args([
+                "--replay",
+                &cassette_path("comment"),
+                "comment",
+                "--url",
+                "https://github.com/owner/repo/pull/2",
+                "--path",
+                "src/lib.rs",
+                "--line",
+                "10",
+                "--body",
+                "Looks good",
+            ])
+            .output()
+            .expect("Failed to execute CLI");
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(!output.status.success());
+        assert!(
+            stderr.contains("No recorded response"),
+            "unexpected stderr: {stderr}"
+        );
+    }
+}
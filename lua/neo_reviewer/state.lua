@@ -70,6 +70,9 @@
 ---@field title string PR title
 ---@field author? string PR author username
 ---@field description? string PR description body
+---@field labels? string[] Label names
+---@field draft? boolean Whether the PR is a draft
+---@field milestone? string Milestone title
 
 ---@alias NRCommentSide "LEFT"|"RIGHT"
 
@@ -85,6 +88,22 @@
 ---@field created_at string ISO date string
 ---@field html_url? string URL to the comment on GitHub
 ---@field in_reply_to_id? integer ID of parent comment if this is a reply
+---@field original_line? integer Line in the commit the comment was left on
+---@field original_commit_id? string Commit the comment was left on
+---@field commit_id? string Commit `line` refers to
+---@field diff_hunk? string Diff hunk shown with the comment on GitHub
+---@field relocation? NRCommentRelocation Where an outdated comment's line is at the PR head
+---@field change_block? integer 0-based index into the file's change_blocks
+---@field line_kind? NRCommentLineKind Kind of diff line the comment sits on
+---@field outside_diff? boolean Whether the comment is on no change block (full PR diff only)
+
+---@alias NRCommentLineKind "added"|"changed"|"deleted"|"context"
+
+---@alias NRRelocationStatus "moved"|"changed"|"gone"
+
+---@class NRCommentRelocation
+---@field status NRRelocationStatus How the commented line changed since the comment
+---@field line? integer Closest line at the PR head, absent when gone
 
 ---@alias NRReviewType "pr"|"local"
 